@@ -1,5 +1,5 @@
 use crate::mem::Vec;
-use core::intrinsics::{powf32, sinf32};
+use core::intrinsics::{ceilf32, floorf32, powf32, sinf32, sqrtf32};
 
 pub trait Points {
     fn points(&self) -> Vec<&[f32; 2]>;
@@ -66,6 +66,18 @@ pub fn sin(x: f32) -> f32 {
     unsafe { sinf32(x) }
 }
 
+pub fn ceil(x: f32) -> f32 {
+    unsafe { ceilf32(x) }
+}
+
+pub fn floor(x: f32) -> f32 {
+    unsafe { floorf32(x) }
+}
+
+pub fn sqrt(x: f32) -> f32 {
+    unsafe { sqrtf32(x) }
+}
+
 pub fn factorial(n: usize) -> usize {
     match n {
         0 | 1 => 1,
@@ -2,9 +2,9 @@
 extern crate alloc;
 
 #[cfg(all(feature = "alloc", not(feature = "mini_vec")))]
-pub use alloc::vec::Vec;
+pub use alloc::vec::{Drain, IntoIter, Vec};
 
 #[cfg(not(feature = "alloc"))]
-pub use vec_64::Vec;
+pub use vec_64::{Drain, IntoIter, Vec};
 
 mod vec_64;
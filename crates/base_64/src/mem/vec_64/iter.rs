@@ -0,0 +1,128 @@
+use super::{free, Vec};
+use core::ptr;
+
+pub struct IntoIter<Item> {
+    data: *mut Item,
+    head: usize,
+    len: usize,
+}
+
+impl<Item> IntoIter<Item> {
+    pub(super) fn new(data: *mut Item, len: usize) -> Self {
+        Self { data, head: 0, len }
+    }
+}
+
+impl<Item> Iterator for IntoIter<Item> {
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Item> {
+        if self.head < self.len {
+            self.head += 1;
+            unsafe { Some(self.data.add(self.head - 1).read()) }
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.head;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<Item> DoubleEndedIterator for IntoIter<Item> {
+    fn next_back(&mut self) -> Option<Item> {
+        if self.head < self.len {
+            self.len -= 1;
+            unsafe { Some(self.data.add(self.len).read()) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<Item> ExactSizeIterator for IntoIter<Item> {}
+
+impl<Item> Drop for IntoIter<Item> {
+    fn drop(&mut self) {
+        unsafe {
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                self.data.add(self.head),
+                self.len - self.head,
+            ));
+            free(self.data as _);
+        }
+    }
+}
+
+pub struct Drain<'a, Item> {
+    list: &'a mut Vec<Item>,
+    head: usize,
+    end: usize,
+    tail_start: usize,
+    tail_len: usize,
+}
+
+impl<'a, Item> Drain<'a, Item> {
+    pub(super) fn new(list: &'a mut Vec<Item>, start: usize, end: usize) -> Self {
+        let tail_len = list.len - end;
+        // Anything leaked through mem::forget stays out of reach of the Vec
+        list.len = start;
+
+        Self {
+            list,
+            head: start,
+            end,
+            tail_start: end,
+            tail_len,
+        }
+    }
+}
+
+impl<Item> Iterator for Drain<'_, Item> {
+    type Item = Item;
+
+    fn next(&mut self) -> Option<Item> {
+        if self.head < self.end {
+            self.head += 1;
+            unsafe { Some(self.list.data.add(self.head - 1).read()) }
+        } else {
+            None
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.head;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<Item> DoubleEndedIterator for Drain<'_, Item> {
+    fn next_back(&mut self) -> Option<Item> {
+        if self.head < self.end {
+            self.end -= 1;
+            unsafe { Some(self.list.data.add(self.end).read()) }
+        } else {
+            None
+        }
+    }
+}
+
+impl<Item> ExactSizeIterator for Drain<'_, Item> {}
+
+impl<Item> Drop for Drain<'_, Item> {
+    fn drop(&mut self) {
+        unsafe {
+            let data = self.list.data;
+            ptr::drop_in_place(ptr::slice_from_raw_parts_mut(
+                data.add(self.head),
+                self.end - self.head,
+            ));
+
+            let start = self.list.len;
+            ptr::copy(data.add(self.tail_start), data.add(start), self.tail_len);
+            self.list.len = start + self.tail_len;
+        }
+    }
+}
@@ -1,5 +1,10 @@
+mod iter;
+
 use core::ffi::c_void;
 use core::mem::size_of;
+use core::ops::{Bound, RangeBounds};
+use core::ptr;
+pub use iter::{Drain, IntoIter};
 
 extern "C" {
     fn malloc(_: usize) -> *mut c_void;
@@ -7,7 +12,6 @@ extern "C" {
     fn free(_: *mut c_void);
 }
 
-#[derive(Debug, Clone)]
 pub struct Vec<Item: Sized> {
     data: *mut Item,
     len: usize,
@@ -22,8 +26,9 @@ impl<Item: Sized> Vec<Item> {
     }
 
     pub fn with_capacity(capacity: usize) -> Self {
+        let capacity = capacity.max(1);
         Vec {
-            data: unsafe { malloc((capacity * Self::ITEM_SIZE) as _) as _ },
+            data: unsafe { malloc((capacity * Self::ITEM_SIZE).max(1) as _) as _ },
             len: 0,
             capacity,
         }
@@ -40,17 +45,144 @@ impl<Item: Sized> Vec<Item> {
         self.len += 1;
     }
 
+    pub fn pop(&mut self) -> Option<Item> {
+        if self.len == 0 {
+            None
+        } else {
+            self.len -= 1;
+            unsafe { Some(self.data.add(self.len).read()) }
+        }
+    }
+
+    pub fn insert(&mut self, idx: usize, item: Item) {
+        assert!(idx <= self.len, "insertion index out of bounds");
+        if self.len >= self.capacity {
+            self.resize(2 * self.capacity);
+        }
+
+        unsafe {
+            let slot = self.data.add(idx);
+            ptr::copy(slot, slot.add(1), self.len - idx);
+            slot.write(item);
+        }
+        self.len += 1;
+    }
+
+    pub fn remove(&mut self, idx: usize) -> Item {
+        assert!(idx < self.len, "removal index out of bounds");
+        unsafe {
+            let slot = self.data.add(idx);
+            let item = slot.read();
+            ptr::copy(slot.add(1), slot, self.len - idx - 1);
+            self.len -= 1;
+
+            item
+        }
+    }
+
+    pub fn swap_remove(&mut self, idx: usize) -> Item {
+        assert!(idx < self.len, "removal index out of bounds");
+        unsafe {
+            let slot = self.data.add(idx);
+            let item = slot.read();
+            self.len -= 1;
+            ptr::copy(self.data.add(self.len), slot, 1);
+
+            item
+        }
+    }
+
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            let tail = ptr::slice_from_raw_parts_mut(unsafe { self.data.add(len) }, self.len - len);
+            // Shrink first so a panicking destructor can't cause a double drop
+            self.len = len;
+            unsafe {
+                ptr::drop_in_place(tail);
+            }
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+
+    pub fn retain(&mut self, mut keep: impl FnMut(&Item) -> bool) {
+        let len = self.len;
+        self.len = 0;
+
+        let mut kept = 0;
+        for idx in 0..len {
+            unsafe {
+                let slot = self.data.add(idx);
+                if keep(&*slot) {
+                    ptr::copy(slot, self.data.add(kept), 1);
+                    kept += 1;
+                } else {
+                    ptr::drop_in_place(slot);
+                }
+            }
+        }
+
+        self.len = kept;
+    }
+
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, Item> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len,
+        };
+        assert!(start <= end && end <= self.len, "drain range out of bounds");
+
+        Drain::new(self, start, end)
+    }
+
+    pub fn extend_from_slice(&mut self, items: &[Item])
+    where
+        Item: Clone,
+    {
+        self.reserve(items.len());
+        for item in items {
+            self.push(item.clone());
+        }
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        let needed = self.len + additional;
+        if needed > self.capacity {
+            self.resize(needed.max(2 * self.capacity));
+        }
+    }
+
     pub fn len(&self) -> usize {
         self.len
     }
 
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     pub fn capacity(&self) -> usize {
         self.capacity
     }
 
+    pub fn as_ptr(&self) -> *const Item {
+        self.data
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut Item {
+        self.data
+    }
+
     fn resize(&mut self, capacity: usize) {
         self.data = unsafe {
-            let new_data = malloc((capacity * Self::ITEM_SIZE) as _) as *mut Item;
+            let new_data = malloc((capacity * Self::ITEM_SIZE).max(1) as _) as *mut Item;
             memcpy(
                 new_data as _,
                 self.data as _,
@@ -64,22 +196,44 @@ impl<Item: Sized> Vec<Item> {
     }
 }
 
-impl<Item> From<&[Item]> for Vec<Item> {
+impl<Item> Default for Vec<Item> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Item: Clone> Clone for Vec<Item> {
+    fn clone(&self) -> Self {
+        self.as_ref().into()
+    }
+}
+
+impl<Item: core::fmt::Debug> core::fmt::Debug for Vec<Item> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<Item: PartialEq> PartialEq for Vec<Item> {
+    fn eq(&self, other: &Self) -> bool {
+        self[..] == other[..]
+    }
+}
+
+impl<Item: Clone> From<&[Item]> for Vec<Item> {
     fn from(items: &[Item]) -> Self {
         let mut list = Vec::with_capacity(items.len());
-        unsafe {
-            memcpy(
-                list.data as _,
-                items.as_ptr() as _,
-                (items.len() * Self::ITEM_SIZE) as _,
-            );
-        }
-
-        list.len = items.len();
+        list.extend_from_slice(items);
         list
     }
 }
 
+impl<Item> AsRef<[Item]> for Vec<Item> {
+    fn as_ref(&self) -> &[Item] {
+        self
+    }
+}
+
 impl<Item> core::ops::Deref for Vec<Item> {
     type Target = [Item];
 
@@ -88,35 +242,87 @@ impl<Item> core::ops::Deref for Vec<Item> {
     }
 }
 
-impl<Item> core::ops::Index<usize> for Vec<Item> {
-    type Output = Item;
+impl<Item> core::ops::DerefMut for Vec<Item> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { core::slice::from_raw_parts_mut(self.data, self.len) }
+    }
+}
+
+impl<Item, Idx: core::slice::SliceIndex<[Item]>> core::ops::Index<Idx> for Vec<Item> {
+    type Output = Idx::Output;
 
-    fn index(&self, idx: usize) -> &Self::Output {
-        unsafe { &*self.data.add(idx) }
+    fn index(&self, idx: Idx) -> &Self::Output {
+        &(**self)[idx]
     }
 }
 
-impl<Item> core::ops::IndexMut<usize> for Vec<Item> {
-    fn index_mut(&mut self, idx: usize) -> &mut Self::Output {
-        unsafe { &mut *self.data.add(idx) }
+impl<Item, Idx: core::slice::SliceIndex<[Item]>> core::ops::IndexMut<Idx> for Vec<Item> {
+    fn index_mut(&mut self, idx: Idx) -> &mut Self::Output {
+        &mut (**self)[idx]
     }
 }
 
 impl<Item> core::iter::FromIterator<Item> for Vec<Item> {
     fn from_iter<I: IntoIterator<Item = Item>>(iter: I) -> Self {
         let mut list = Self::with_capacity(1);
+        list.extend(iter);
+
+        list
+    }
+}
+
+impl<Item> Extend<Item> for Vec<Item> {
+    fn extend<I: IntoIterator<Item = Item>>(&mut self, iter: I) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
         for x in iter {
-            list.push(x);
+            self.push(x);
         }
+    }
+}
 
-        list
+impl<'a, Item: Copy + 'a> Extend<&'a Item> for Vec<Item> {
+    fn extend<I: IntoIterator<Item = &'a Item>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<Item> IntoIterator for Vec<Item> {
+    type Item = Item;
+    type IntoIter = IntoIter<Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        let list = core::mem::ManuallyDrop::new(self);
+        IntoIter::new(list.data, list.len)
+    }
+}
+
+impl<'a, Item> IntoIterator for &'a Vec<Item> {
+    type Item = &'a Item;
+    type IntoIter = core::slice::Iter<'a, Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, Item> IntoIterator for &'a mut Vec<Item> {
+    type Item = &'a mut Item;
+    type IntoIter = core::slice::IterMut<'a, Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
     }
 }
 
 impl<Item> Drop for Vec<Item> {
     fn drop(&mut self) {
         unsafe {
+            ptr::drop_in_place(&mut **self as *mut [Item]);
             free(self.data as _);
         }
     }
 }
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,135 @@
+use super::Vec;
+use core::cell::Cell;
+
+/// Counts its drops into a shared cell
+struct Counted<'a>(&'a Cell<usize>);
+
+impl Drop for Counted<'_> {
+    fn drop(&mut self) {
+        self.0.set(self.0.get() + 1);
+    }
+}
+
+fn counted(drops: &Cell<usize>, n: usize) -> Vec<Counted<'_>> {
+    (0..n).map(|_| Counted(drops)).collect()
+}
+
+#[test]
+fn grows_past_capacity() {
+    let mut list = Vec::with_capacity(1);
+    for idx in 0..100u32 {
+        list.push(idx);
+    }
+
+    assert_eq!(list.len(), 100);
+    assert!(list.capacity() >= 100);
+    assert!(list.iter().copied().eq(0..100));
+
+    list.reserve(1000);
+    assert!(list.capacity() >= 1100);
+    assert!(list.iter().copied().eq(0..100));
+}
+
+#[test]
+fn insert_remove() {
+    let mut list: Vec<u32> = [1, 2, 4].as_ref().into();
+    list.insert(2, 3);
+    list.insert(0, 0);
+    assert_eq!(&list[..], &[0, 1, 2, 3, 4]);
+
+    assert_eq!(list.remove(1), 1);
+    assert_eq!(list.swap_remove(0), 0);
+    assert_eq!(&list[..], &[4, 2, 3]);
+    assert_eq!(list.pop(), Some(3));
+}
+
+#[test]
+fn drops_every_item() {
+    let drops = Cell::new(0);
+    drop(counted(&drops, 10));
+    assert_eq!(drops.get(), 10);
+}
+
+#[test]
+fn truncate_and_clear_drop_the_tail() {
+    let drops = Cell::new(0);
+    let mut list = counted(&drops, 10);
+
+    list.truncate(12);
+    assert_eq!(drops.get(), 0);
+    list.truncate(4);
+    assert_eq!((list.len(), drops.get()), (4, 6));
+    list.clear();
+    assert_eq!((list.len(), drops.get()), (0, 10));
+
+    drop(list);
+    assert_eq!(drops.get(), 10);
+}
+
+#[test]
+fn retain_drops_rejected() {
+    let drops = Cell::new(0);
+    let mut list: Vec<(u32, Counted)> = (0..10).map(|idx| (idx, Counted(&drops))).collect();
+
+    list.retain(|(idx, _)| idx % 3 == 0);
+    assert_eq!(drops.get(), 6);
+    assert!(list.iter().map(|(idx, _)| *idx).eq([0, 3, 6, 9]));
+
+    drop(list);
+    assert_eq!(drops.get(), 10);
+}
+
+#[test]
+fn extend() {
+    let mut list = Vec::new();
+    list.extend(0..3u32);
+    list.extend(&[3, 4]);
+    list.extend_from_slice(&[5, 6]);
+    assert!(list.iter().copied().eq(0..7));
+}
+
+#[test]
+fn into_iter_partly_consumed() {
+    let drops = Cell::new(0);
+    let mut iter = counted(&drops, 10).into_iter();
+
+    drop(iter.next());
+    drop(iter.next_back());
+    assert_eq!((iter.len(), drops.get()), (8, 2));
+
+    drop(iter);
+    assert_eq!(drops.get(), 10);
+}
+
+#[test]
+fn into_iter_order() {
+    let list: Vec<u32> = (0..5).collect();
+    let mut iter = list.into_iter();
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next_back(), Some(4));
+    assert!(iter.eq(1..4));
+}
+
+#[test]
+fn drain_keeps_tail() {
+    let drops = Cell::new(0);
+    let mut list: Vec<(u32, Counted)> = (0..10).map(|idx| (idx, Counted(&drops))).collect();
+
+    let mut drain = list.drain(2..6);
+    assert_eq!(drain.next().map(|(idx, _)| idx), Some(2));
+    drop(drain);
+    assert_eq!(drops.get(), 4);
+    assert!(list.iter().map(|(idx, _)| *idx).eq([0, 1, 6, 7, 8, 9]));
+
+    drop(list);
+    assert_eq!(drops.get(), 10);
+}
+
+#[test]
+fn clone_is_deep() {
+    let list: Vec<u32> = (0..5).collect();
+    let mut copy = list.clone();
+    copy[0] = 9;
+    assert_eq!(list[0], 0);
+    assert_eq!(&copy[1..], &list[1..]);
+}
@@ -0,0 +1,198 @@
+//! Derives for gfx_64, parsed by hand to keep syn out of the build
+extern crate proc_macro;
+
+use proc_macro::{Delimiter, TokenStream, TokenTree};
+
+/// Implements `Vertex` for a `#[repr(C)]` struct whose fields are all `Attribute`s, bound
+/// to locations in field order. Paths default to `::gfx_64`, set another with
+/// `#[vertex(crate = "underscore_64::gfx")]`
+#[proc_macro_derive(Vertex, attributes(vertex))]
+pub fn derive_vertex(input: TokenStream) -> TokenStream {
+    let out = match vertex(input) {
+        Ok(out) => out,
+        Err(msg) => format!("compile_error!({:?});", msg),
+    };
+    out.parse().unwrap()
+}
+
+fn vertex(input: TokenStream) -> Result<String, &'static str> {
+    let mut tokens = input.into_iter().peekable();
+    let mut repr_c = false;
+    let mut path = String::from("::gfx_64");
+
+    while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+        if punct.as_char() != '#' {
+            break;
+        }
+        tokens.next();
+        let attr = match tokens.next() {
+            Some(TokenTree::Group(group)) => group.stream(),
+            _ => return Err("malformed attribute"),
+        };
+
+        let mut attr = attr.into_iter();
+        match (attr.next(), attr.next()) {
+            (Some(TokenTree::Ident(name)), Some(TokenTree::Group(args)))
+                if name.to_string() == "repr" =>
+            {
+                repr_c |= args.stream().into_iter().any(|arg| arg.to_string() == "C");
+            }
+            (Some(TokenTree::Ident(name)), Some(TokenTree::Group(args)))
+                if name.to_string() == "vertex" =>
+            {
+                let args: Vec<_> = args.stream().into_iter().collect();
+                match args.as_slice() {
+                    [TokenTree::Ident(key), TokenTree::Punct(eq), TokenTree::Literal(lit)]
+                        if key.to_string() == "crate" && eq.as_char() == '=' =>
+                    {
+                        path = lit.to_string().trim_matches('"').to_string();
+                    }
+                    _ => return Err("expected #[vertex(crate = \"path\")]"),
+                }
+            }
+            _ => {}
+        }
+    }
+    if !repr_c {
+        return Err("Vertex needs #[repr(C)] so the field order is what GL reads");
+    }
+
+    skip_visibility(&mut tokens);
+    match tokens.next() {
+        Some(TokenTree::Ident(ident)) if ident.to_string() == "struct" => {}
+        _ => return Err("Vertex can only be derived for structs"),
+    }
+    let name = match tokens.next() {
+        Some(TokenTree::Ident(name)) => name.to_string(),
+        _ => return Err("expected a struct name"),
+    };
+    let fields = match tokens.next() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => group.stream(),
+        Some(TokenTree::Punct(punct)) if punct.as_char() == '<' => {
+            return Err("Vertex can't be derived for generic structs")
+        }
+        _ => return Err("Vertex needs named fields"),
+    };
+
+    let mut out = format!("impl {}::Vertex for {} {{ fn bind() {{", path, name);
+    for (idx, (field, ty)) in split_fields(fields)?.iter().enumerate() {
+        out += &format!(
+            "{}::bind_attribute::<{}>({}, ::core::mem::size_of::<Self>(), ::core::mem::offset_of!(Self, {}));",
+            path, ty, idx, field
+        );
+    }
+    out += "}}";
+
+    Ok(out)
+}
+
+fn skip_visibility(tokens: &mut core::iter::Peekable<impl Iterator<Item = TokenTree>>) {
+    if let Some(TokenTree::Ident(ident)) = tokens.peek() {
+        if ident.to_string() == "pub" {
+            tokens.next();
+            if let Some(TokenTree::Group(group)) = tokens.peek() {
+                if group.delimiter() == Delimiter::Parenthesis {
+                    tokens.next();
+                }
+            }
+        }
+    }
+}
+
+/// Names and types of each field, splitting on commas outside of `<>`
+fn split_fields(fields: TokenStream) -> Result<Vec<(String, String)>, &'static str> {
+    let mut tokens = fields.into_iter().peekable();
+    let mut out = Vec::new();
+    loop {
+        // Field attributes, e.g. docs
+        while let Some(TokenTree::Punct(punct)) = tokens.peek() {
+            if punct.as_char() != '#' {
+                break;
+            }
+            tokens.next();
+            tokens.next();
+        }
+        skip_visibility(&mut tokens);
+
+        let name = match tokens.next() {
+            Some(TokenTree::Ident(name)) => name.to_string(),
+            None => return Ok(out),
+            _ => return Err("expected a field name"),
+        };
+        match tokens.next() {
+            Some(TokenTree::Punct(punct)) if punct.as_char() == ':' => {}
+            _ => return Err("expected a field type"),
+        }
+
+        // Collected as a stream so joint punctuation like `::` survives the round trip
+        let mut ty = Vec::new();
+        let mut depth = 0;
+        for token in tokens.by_ref() {
+            if let TokenTree::Punct(punct) = &token {
+                match punct.as_char() {
+                    '<' => depth += 1,
+                    '>' => depth -= 1,
+                    ',' if depth == 0 => break,
+                    _ => {}
+                }
+            }
+            ty.push(token);
+        }
+        out.push((name, TokenStream::from_iter(ty).to_string()));
+    }
+}
+
+/// The derive's own errors, each next to a copy that builds so a failure can only be the derive's
+///
+/// ```
+/// mod gfx {
+///     pub trait Vertex {
+///         fn bind();
+///     }
+///     pub fn bind_attribute<A>(_: u32, _: usize, _: usize) {}
+/// }
+///
+/// #[derive(derive_64::Vertex)]
+/// #[vertex(crate = "gfx")]
+/// #[repr(C)]
+/// struct Fine {
+///     pos: [f32; 2],
+/// }
+/// ```
+///
+/// Without `#[repr(C)]`:
+///
+/// ```compile_fail
+/// mod gfx {
+///     pub trait Vertex {
+///         fn bind();
+///     }
+///     pub fn bind_attribute<A>(_: u32, _: usize, _: usize) {}
+/// }
+///
+/// #[derive(derive_64::Vertex)]
+/// #[vertex(crate = "gfx")]
+/// struct Unordered {
+///     pos: [f32; 2],
+/// }
+/// ```
+///
+/// Generic:
+///
+/// ```compile_fail
+/// mod gfx {
+///     pub trait Vertex {
+///         fn bind();
+///     }
+///     pub fn bind_attribute<A>(_: u32, _: usize, _: usize) {}
+/// }
+///
+/// #[derive(derive_64::Vertex)]
+/// #[vertex(crate = "gfx")]
+/// #[repr(C)]
+/// struct Generic<T> {
+///     pos: T,
+/// }
+/// ```
+#[cfg(doctest)]
+struct CompileFail;
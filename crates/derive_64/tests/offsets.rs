@@ -0,0 +1,80 @@
+use derive_64::Vertex;
+use std::{any::type_name, cell::RefCell, vec::Vec};
+
+// Stands in for gfx_64, recording what the derive binds instead of calling GL
+mod gfx {
+    use super::*;
+
+    thread_local! {
+        pub static BOUND: RefCell<Vec<(u32, usize, usize, &'static str)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    pub trait Vertex {
+        fn bind();
+    }
+
+    pub fn bind_attribute<A>(idx: u32, stride: usize, offset: usize) {
+        BOUND.with(|bound| {
+            bound
+                .borrow_mut()
+                .push((idx, stride, offset, type_name::<A>()))
+        });
+    }
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    pub struct Pair<A, B>(pub A, pub B);
+}
+
+fn bind<V: gfx::Vertex>() -> Vec<(u32, usize, usize, &'static str)> {
+    gfx::BOUND.with(|bound| bound.borrow_mut().clear());
+    V::bind();
+    gfx::BOUND.with(|bound| bound.borrow().clone())
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Vertex)]
+#[vertex(crate = "gfx")]
+#[repr(C)]
+struct Textured {
+    pos: [f32; 3],
+    uv: [f32; 2],
+    color: [u8; 4],
+}
+
+#[test]
+fn field_offsets() {
+    assert_eq!(
+        bind::<Textured>(),
+        [
+            (0, 24, 0, type_name::<[f32; 3]>()),
+            (1, 24, 12, type_name::<[f32; 2]>()),
+            (2, 24, 20, type_name::<[u8; 4]>()),
+        ]
+    );
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Vertex)]
+#[repr(C)]
+#[vertex(crate = "gfx")]
+pub struct Padded {
+    /// Docs and visibility on fields are skipped
+    pub(crate) id: u8,
+    pub weights: gfx::Pair<u16, [f32; 2]>,
+    normal: [i16; 3],
+}
+
+#[test]
+fn padding_and_generic_fields() {
+    // The pair is aligned to its floats, so `id` is followed by 3 bytes of padding, and the
+    // stride is rounded up to 4 after the 6 byte normal
+    assert_eq!(
+        bind::<Padded>(),
+        [
+            (0, 24, 0, type_name::<u8>()),
+            (1, 24, 4, type_name::<gfx::Pair<u16, [f32; 2]>>()),
+            (2, 24, 16, type_name::<[i16; 3]>()),
+        ]
+    );
+}
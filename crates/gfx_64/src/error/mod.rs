@@ -0,0 +1,141 @@
+use crate::{
+    gl::{self, types::GLenum},
+    resource::shader::Stage,
+};
+use base_64::mem::Vec;
+#[cfg(feature = "display")]
+use core::fmt;
+use sdl_64::Message;
+
+#[derive(Debug, Clone)]
+pub enum Error {
+    Sdl(sdl_64::Error),
+    /// A GL entry point the driver doesn't export, nul included
+    MissingProc(&'static str),
+    /// What `glGetError` reported
+    Gl(GLenum),
+    /// Every problem found building a pipeline, in the order the stages were given
+    Shader(Vec<Diagnostic>),
+    /// A value that doesn't match the named uniform's reflected GLSL type
+    UniformType {
+        name: Message,
+        ty: GLenum,
+    },
+    /// A buffer whose element is neither the named block's reflected size nor its array stride
+    BlockSize {
+        name: Message,
+        size: usize,
+        expected: usize,
+        /// 0 if the block has no array
+        stride: usize,
+    },
+    /// An element size not padded to what the block layout requires
+    BlockLayout {
+        size: usize,
+        align: usize,
+    },
+    /// Past the driver's binding points for the buffer's target
+    BindingPoint(u32),
+    /// A bound range past the end or off the driver's offset alignment
+    BufferRange {
+        offset: usize,
+        align: usize,
+    },
+}
+
+impl From<sdl_64::Error> for Error {
+    fn from(err: sdl_64::Error) -> Self {
+        Error::Sdl(err)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Phase {
+    Compile(Stage),
+    Link,
+    Validate,
+}
+
+/// One line of a compile, link or validate log
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub phase: Phase,
+    /// Empty for link and validate logs, which aren't tied to one file
+    pub path: &'static str,
+    /// Line in `path` the driver blamed, if it named one
+    pub line: Option<u32>,
+    pub message: Message,
+    /// The offending source line
+    pub code: Option<Message>,
+}
+
+/// Turns any pending GL error into an `Err`
+pub fn check() -> Result<(), Error> {
+    match unsafe { gl::GetError() } {
+        gl::NO_ERROR => Ok(()),
+        err => Err(Error::Gl(err)),
+    }
+}
+
+#[cfg(feature = "display")]
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let phase = match self.phase {
+            Phase::Compile(stage) => stage.name(),
+            Phase::Link => "link",
+            Phase::Validate => "validate",
+        };
+        match self.line {
+            Some(line) => write!(f, "{}:{}: ", self.path, line)?,
+            None if !self.path.is_empty() => write!(f, "{}: ", self.path)?,
+            None => {}
+        }
+        write!(f, "[{}] {}", phase, self.message)?;
+        if let Some(code) = &self.code {
+            write!(f, "\n    | {}", code)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "display")]
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Sdl(err) => fmt::Display::fmt(err, f),
+            Error::MissingProc(name) => {
+                write!(f, "GL driver lacks {}", name.trim_end_matches('\0'))
+            }
+            Error::Gl(code) => write!(f, "GL error {:#06x}", code),
+            Error::UniformType { name, ty } => {
+                write!(f, "wrong type for uniform '{}' of type {:#06x}", name, ty)
+            }
+            Error::BlockSize {
+                name,
+                size,
+                expected,
+                stride,
+            } => write!(
+                f,
+                "{} byte element doesn't fit block '{}' of {} bytes and {} byte elements",
+                size, name, expected, stride
+            ),
+            Error::BlockLayout { size, align } => {
+                write!(f, "{} byte element isn't padded to {}", size, align)
+            }
+            Error::BindingPoint(binding) => write!(f, "no binding point {}", binding),
+            Error::BufferRange { offset, align } => {
+                write!(f, "can't bind from offset {} aligned to {}", offset, align)
+            }
+            Error::Shader(diagnostics) => {
+                f.write_str("pipeline failed to build")?;
+                for diagnostic in diagnostics.iter() {
+                    write!(f, "\n{}", diagnostic)?;
+                }
+
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use base_64::mem::Vec;
+
+const PNG_SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+const DEFLATE_BLOCK: usize = 0xffff;
+
+#[derive(Clone, Debug)]
+pub struct Image {
+    pub dim: [usize; 2],
+    pub pixels: Vec<u8>,
+}
+
+impl Image {
+    pub fn new([w, h]: [usize; 2]) -> Self {
+        Self {
+            dim: [w, h],
+            pixels: (0..w * h * 4).map(|_| 0).collect(),
+        }
+    }
+
+    pub fn pixel(&self, [x, y]: [usize; 2]) -> [u8; 4] {
+        let idx = 4 * (y * self.dim[0] + x);
+        [
+            self.pixels[idx],
+            self.pixels[idx + 1],
+            self.pixels[idx + 2],
+            self.pixels[idx + 3],
+        ]
+    }
+
+    pub(crate) fn flip_rows(&mut self) {
+        let stride = 4 * self.dim[0];
+        let h = self.dim[1];
+        for row in 0..h / 2 {
+            let (top, bottom) = self.pixels.split_at_mut((h - row - 1) * stride);
+            top[row * stride..(row + 1) * stride].swap_with_slice(&mut bottom[..stride]);
+        }
+    }
+
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let [w, h] = self.dim;
+        let mut ppm = Vec::with_capacity(32 + 3 * w * h);
+        ppm.extend_from_slice(b"P6\n");
+        push_decimal(&mut ppm, w);
+        ppm.push(b' ');
+        push_decimal(&mut ppm, h);
+        ppm.extend_from_slice(b"\n255\n");
+
+        for rgba in self.pixels.chunks(4) {
+            ppm.extend_from_slice(&rgba[..3]);
+        }
+
+        ppm
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let [w, h] = self.dim;
+        let mut png = Vec::with_capacity(64 + self.pixels.len() + h);
+        png.extend_from_slice(&PNG_SIGNATURE);
+
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&(w as u32).to_be_bytes());
+        header.extend_from_slice(&(h as u32).to_be_bytes());
+        // 8 bits per channel RGBA, no interlacing
+        header.extend_from_slice(&[8, 6, 0, 0, 0]);
+        push_chunk(&mut png, b"IHDR", &header);
+
+        let mut scanlines = Vec::with_capacity(self.pixels.len() + h);
+        for row in self.pixels.chunks(4 * w) {
+            scanlines.push(0);
+            scanlines.extend_from_slice(row);
+        }
+
+        // zlib stream of stored deflate blocks, no compression
+        let mut zlib =
+            Vec::with_capacity(scanlines.len() + 5 * (scanlines.len() / DEFLATE_BLOCK) + 16);
+        zlib.extend_from_slice(&[0x78, 0x01]);
+        let mut rest = &scanlines[..];
+        loop {
+            let (block, tail) = rest.split_at(rest.len().min(DEFLATE_BLOCK));
+            let len = block.len() as u16;
+            zlib.push(tail.is_empty() as u8);
+            zlib.extend_from_slice(&len.to_le_bytes());
+            zlib.extend_from_slice(&(!len).to_le_bytes());
+            zlib.extend_from_slice(block);
+
+            if tail.is_empty() {
+                break;
+            }
+            rest = tail;
+        }
+        zlib.extend_from_slice(&adler32(&scanlines).to_be_bytes());
+        push_chunk(&mut png, b"IDAT", &zlib);
+
+        push_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    #[cfg(feature = "std")]
+    pub fn save_ppm(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, &*self.to_ppm())
+    }
+
+    #[cfg(feature = "std")]
+    pub fn save_png(&self, path: impl AsRef<std::path::Path>) -> std::io::Result<()> {
+        std::fs::write(path, &*self.to_png())
+    }
+}
+
+fn push_decimal(out: &mut Vec<u8>, mut n: usize) {
+    let mut digits = [0u8; 20];
+    let mut len = 0;
+    loop {
+        digits[len] = b'0' + (n % 10) as u8;
+        len += 1;
+        n /= 10;
+        if n == 0 {
+            break;
+        }
+    }
+
+    for &digit in digits[..len].iter().rev() {
+        out.push(digit);
+    }
+}
+
+fn push_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+
+    let crc = crc32(!0, kind);
+    png.extend_from_slice(&(!crc32(crc, data)).to_be_bytes());
+}
+
+fn crc32(mut crc: u32, data: &[u8]) -> u32 {
+    for &byte in data {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                0xedb8_8320 ^ (crc >> 1)
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    crc
+}
+
+fn adler32(data: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests;
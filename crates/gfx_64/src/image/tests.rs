@@ -0,0 +1,53 @@
+use super::{adler32, crc32, Image, PNG_SIGNATURE};
+use base_64::mem::Vec;
+
+// 2x1: opaque red, then half transparent blue
+fn image() -> Image {
+    let mut image = Image::new([2, 1]);
+    image
+        .pixels
+        .copy_from_slice(&[255, 0, 0, 255, 0, 0, 255, 128]);
+    image
+}
+
+#[test]
+fn checksums() {
+    assert_eq!(!crc32(!0, b"IEND"), 0xae42_6082);
+    assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+}
+
+#[test]
+fn png() {
+    let png = image().to_png();
+    let mut expected = Vec::new();
+    expected.extend_from_slice(&PNG_SIGNATURE);
+    expected.extend_from_slice(&[
+        0, 0, 0, 13, b'I', b'H', b'D', b'R', // length, type
+        0, 0, 0, 2, 0, 0, 0, 1, 8, 6, 0, 0, 0, // 2x1, 8 bit RGBA
+        0xf4, 0x22, 0x7f, 0x8a, // crc
+        0, 0, 0, 20, b'I', b'D', b'A', b'T', // length, type
+        0x78, 0x01, 1, 9, 0, 0xf6, 0xff, // zlib header, last stored block of 9 bytes
+        0, 255, 0, 0, 255, 0, 0, 255, 128, // filter byte and row
+        0x0f, 0x7a, 0x03, 0x7e, // adler
+        0x4a, 0x6e, 0x70, 0x0c, // crc
+        0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82,
+    ]);
+
+    assert_eq!(&png[..], &expected[..]);
+}
+
+#[test]
+fn ppm() {
+    assert_eq!(&image().to_ppm()[..], b"P6\n2 1\n255\n\xff\0\0\0\0\xff");
+}
+
+#[test]
+fn flip_rows() {
+    let mut image = Image::new([1, 3]);
+    image
+        .pixels
+        .copy_from_slice(&[1, 1, 1, 1, 2, 2, 2, 2, 3, 3, 3, 3]);
+    image.flip_rows();
+    assert_eq!(image.pixel([0, 0]), [3; 4]);
+    assert_eq!(image.pixel([0, 2]), [1; 4]);
+}
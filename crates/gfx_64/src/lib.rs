@@ -1,4 +1,9 @@
 #![no_std]
+#[cfg(feature = "std")]
+extern crate std;
+
+pub mod error;
+pub mod image;
 pub mod resource;
 pub mod gl {
     include!(concat!(env!("OUT_DIR"), "/bindings.rs"));
@@ -7,53 +12,110 @@ pub mod gl {
 use base_64::mem::Vec;
 use gl::types::*;
 
+pub use derive_64::Vertex;
+pub use error::{Diagnostic, Error, Phase};
+pub use image::Image;
 pub use resource::{
-    buffer::{Buffer, Usage},
+    blend::{Blend, Equation, Factor},
+    buffer::{Buffer, StorageBuffer, UniformBuffer, Usage},
     framebuffer::{Attachment, Framebuffer},
-    mesh::{Mesh, MeshId, Topology, Vertex},
-    pipeline::Pipeline,
-    shader::Shader,
-    texture::{Format, Target, Texture},
-    window::Window,
-    Draw, RenderTarget, Resource, Stencil, Uniform,
+    mesh::{
+        bind_attribute, Attribute, Index, Indices, Mesh, MeshId, Norm, Primitive, Topology,
+        Vertex, F16,
+    },
+    pipeline::{Block, Pipeline, Reflection, Variable},
+    shader::{Shader, Source, Stage},
+    texture::{Filter, Format, Target, Texture},
+    window::{Fullscreen, Position, SwapInterval, Window, WindowBuilder},
+    Draw, RenderTarget, Resource, Sampler, Stencil, Uniform,
 };
 
+// Newest entry points used at startup, anything older comes with them
+const REQUIRED_PROCS: &[&str] = &["glDebugMessageCallback\0", "glCreateProgram\0"];
+
 #[derive(Debug)]
 pub struct GfxSystem {
     win: Window,
+    dim: [i32; 2],
 }
 
 impl GfxSystem {
-    pub fn new(name: &[u8], w: i32, h: i32) -> Result<Self, ()> {
+    pub fn new(window: WindowBuilder) -> Result<Self, Error> {
         log::info!("initializing GfxSystem");
-        let win = Window::new(name, w, h)?;
-
-        let gl_fn = |s: &str| unsafe {
-            let gl = sdl_64::load_gl(s.as_ptr() as *const i8);
+        Self::with_window(window.build()?)
+    }
 
-            if gl.is_null() {
-                panic!()
-            } else {
-                gl as *const _
-            }
-        };
+    pub fn headless(name: &[u8], w: i32, h: i32) -> Result<Self, Error> {
+        Self::new(WindowBuilder::new(name, w, h).headless(true))
+    }
 
+    fn with_window(win: Window) -> Result<Self, Error> {
         log::info!("window created");
 
+        // The bindings link statically, so a missing entry point would only show up as a crash
+        for &name in REQUIRED_PROCS {
+            if unsafe { sdl_64::load_gl(name.as_ptr() as *const i8) }.is_null() {
+                log::error!("GL driver lacks {}", name.trim_end_matches('\0'));
+                return Err(Error::MissingProc(name));
+            }
+        }
+
         unsafe {
             gl::Enable(gl::DEBUG_OUTPUT);
             gl::DebugMessageCallback(Some(debug_log), core::ptr::null());
+            if win.is_srgb() {
+                gl::Enable(gl::FRAMEBUFFER_SRGB);
+            }
         }
 
-        Ok(Self { win })
+        error::check()?;
+        let dim = win.drawable_size();
+        Ok(Self { win, dim })
+    }
+
+    /// Picks up the window's new drawable size, call when it reports a size change
+    pub fn resize(&mut self) -> [i32; 2] {
+        self.dim = self.win.drawable_size();
+        log::debug!("resized to {}x{} pixels", self.dim[0], self.dim[1]);
+        self.dim
+    }
+
+    pub fn drawable_size(&self) -> [i32; 2] {
+        self.dim
+    }
+
+    pub fn window_size(&self) -> [i32; 2] {
+        self.win.size()
     }
 
     pub fn draw(&self, draw_fn: impl Fn()) {
+        self.begin();
+        draw_fn();
+        self.present();
+    }
+
+    /// Binds and clears the window for a new frame
+    pub fn begin(&self) {
         log::debug!("drawing to window");
         self.win.bind();
+        self.win.viewport([0, 0], self.dim);
+        self.win.clear_color([0.0, 0.0, 0.0, 1.0]);
+    }
+
+    pub fn present(&self) {
+        self.win.swap();
+    }
+
+    pub fn render(&self, draw_fn: impl Fn()) -> Image {
+        log::debug!("rendering window offscreen");
+        self.win.bind();
+        self.win.viewport([0, 0], self.dim);
         self.win.clear_color([0.0, 0.0, 0.0, 1.0]);
         draw_fn();
+
+        let image = self.win.read_pixels([0, 0], self.dim);
         self.win.swap();
+        image
     }
 }
 
@@ -67,7 +129,9 @@ pub extern "system" fn debug_log(
     usr_param: *mut GLvoid,
 ) {
     let msg = unsafe { core::ffi::CStr::from_ptr(msg) };
-    log::debug!("{}", msg.to_str().expect("couldn't parse as utf8"));
+    if let Ok(msg) = msg.to_str() {
+        log::debug!("{}", msg);
+    }
 }
 
 /*
@@ -0,0 +1,79 @@
+use crate::gl;
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Factor {
+    Zero = gl::ZERO,
+    One = gl::ONE,
+    SrcColor = gl::SRC_COLOR,
+    OneMinusSrcColor = gl::ONE_MINUS_SRC_COLOR,
+    DstColor = gl::DST_COLOR,
+    OneMinusDstColor = gl::ONE_MINUS_DST_COLOR,
+    SrcAlpha = gl::SRC_ALPHA,
+    OneMinusSrcAlpha = gl::ONE_MINUS_SRC_ALPHA,
+    DstAlpha = gl::DST_ALPHA,
+    OneMinusDstAlpha = gl::ONE_MINUS_DST_ALPHA,
+}
+
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Equation {
+    Add = gl::FUNC_ADD,
+    Subtract = gl::FUNC_SUBTRACT,
+    ReverseSubtract = gl::FUNC_REVERSE_SUBTRACT,
+    Min = gl::MIN,
+    Max = gl::MAX,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Blend {
+    Off,
+    Alpha,
+    Premultiplied,
+    Additive,
+    Custom {
+        equation: Equation,
+        src: Factor,
+        dst: Factor,
+        src_alpha: Factor,
+        dst_alpha: Factor,
+    },
+}
+
+impl Blend {
+    pub fn apply(self) {
+        use Factor::*;
+
+        let (equation, src, dst, src_alpha, dst_alpha) = match self {
+            Blend::Off => {
+                unsafe {
+                    gl::Disable(gl::BLEND);
+                }
+                return;
+            }
+            Blend::Alpha => (
+                Equation::Add,
+                SrcAlpha,
+                OneMinusSrcAlpha,
+                One,
+                OneMinusSrcAlpha,
+            ),
+            Blend::Premultiplied => (Equation::Add, One, OneMinusSrcAlpha, One, OneMinusSrcAlpha),
+            Blend::Additive => (Equation::Add, One, One, One, One),
+            Blend::Custom {
+                equation,
+                src,
+                dst,
+                src_alpha,
+                dst_alpha,
+            } => (equation, src, dst, src_alpha, dst_alpha),
+        };
+
+        log::trace!("blending {:?}", self);
+        unsafe {
+            gl::Enable(gl::BLEND);
+            gl::BlendEquation(equation as _);
+            gl::BlendFuncSeparate(src as _, dst as _, src_alpha as _, dst_alpha as _);
+        }
+    }
+}
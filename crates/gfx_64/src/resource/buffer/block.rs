@@ -0,0 +1,201 @@
+use super::{Buffer, Usage};
+use crate::{gl, gl::types::*, Error};
+use core::{marker::PhantomData, mem::size_of};
+
+/// How a block's members are packed, which decides the binding target and what sizes are legal
+pub trait Layout {
+    const TARGET: GLenum;
+    /// Interface to reflect the block through, see `Pipeline::bind_block`
+    const INTERFACE: GLenum;
+    const MAX_BINDINGS: GLenum;
+    /// Every element must be padded to a multiple of this
+    const ALIGN: usize;
+}
+
+/// `layout(std140) uniform`: structs and array elements are rounded up to a vec4
+pub struct Std140;
+
+impl Layout for Std140 {
+    const TARGET: GLenum = gl::UNIFORM_BUFFER;
+    const INTERFACE: GLenum = gl::UNIFORM_BLOCK;
+    const MAX_BINDINGS: GLenum = gl::MAX_UNIFORM_BUFFER_BINDINGS;
+    const ALIGN: usize = 16;
+}
+
+/// `layout(std430) buffer`: packed like C, except that a vec3 still takes the room of a vec4
+pub struct Std430;
+
+impl Layout for Std430 {
+    const TARGET: GLenum = gl::SHADER_STORAGE_BUFFER;
+    const INTERFACE: GLenum = gl::SHADER_STORAGE_BLOCK;
+    const MAX_BINDINGS: GLenum = gl::MAX_SHADER_STORAGE_BUFFER_BINDINGS;
+    const ALIGN: usize = 4;
+}
+
+pub type UniformBuffer<T> = BlockBuffer<T, Std140>;
+pub type StorageBuffer<T> = BlockBuffer<T, Std430>;
+
+/// An array of `T` bound to an indexed binding point, where every pipeline whose block is
+/// bound to the same point sees it. `T` should be `#[repr(C)]` and padded by hand to `L`
+pub struct BlockBuffer<T, L> {
+    buf: Buffer,
+    binding: GLuint,
+    _block: PhantomData<(T, L)>,
+}
+
+impl<T: Copy, L: Layout> BlockBuffer<T, L> {
+    /// Uploads `data` and binds all of it to `binding`
+    pub fn new(binding: GLuint, data: &[T], usage: Usage) -> Result<Self, Error> {
+        if !size_of::<T>().is_multiple_of(L::ALIGN) {
+            log::error!(
+                "{} is {} bytes, not padded to {}",
+                core::any::type_name::<T>(),
+                size_of::<T>(),
+                L::ALIGN
+            );
+            return Err(Error::BlockLayout {
+                size: size_of::<T>(),
+                align: L::ALIGN,
+            });
+        }
+
+        let mut max = 0;
+        unsafe {
+            gl::GetIntegerv(L::MAX_BINDINGS, &mut max);
+        }
+        if binding >= max as GLuint {
+            log::error!("binding point {} out of {}", binding, max);
+            return Err(Error::BindingPoint(binding));
+        }
+
+        let buffer = Self {
+            buf: Buffer::new(L::TARGET, usage, data),
+            binding,
+            _block: PhantomData,
+        };
+        buffer.bind_base();
+        Ok(buffer)
+    }
+
+    pub fn binding(&self) -> GLuint {
+        self.binding
+    }
+
+    pub fn len(&self) -> usize {
+        self.buf.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buf.len == 0
+    }
+
+    /// Size in bytes
+    pub fn size(&self) -> usize {
+        self.buf.len * size_of::<T>()
+    }
+
+    /// Rebinds the whole buffer to its binding point, e.g. after something else took it
+    pub fn bind_base(&self) {
+        unsafe {
+            gl::BindBufferBase(L::TARGET, self.binding, self.buf.id);
+        }
+    }
+
+    /// Binds `count` elements from `start`, which has to land on the driver's offset alignment
+    pub fn bind_range(&self, binding: GLuint, start: usize, count: usize) -> Result<(), Error> {
+        let offset = start * size_of::<T>();
+        let align = offset_alignment(L::TARGET);
+        if !offset.is_multiple_of(align) || start + count > self.len() {
+            log::error!(
+                "can't bind {}..{} of buffer {}",
+                start,
+                start + count,
+                self.buf.id
+            );
+            return Err(Error::BufferRange { offset, align });
+        }
+
+        unsafe {
+            gl::BindBufferRange(
+                L::TARGET,
+                binding,
+                self.buf.id,
+                offset as _,
+                (count * size_of::<T>()) as _,
+            );
+        }
+        Ok(())
+    }
+
+    /// Overwrites elements from `start` on, anything past the end is dropped
+    pub fn update(&mut self, start: usize, data: &[T]) {
+        let count = data.len().min(self.len().saturating_sub(start));
+        if count < data.len() {
+            log::warn!(
+                "buffer {} update cut short by {}",
+                self.buf.id,
+                data.len() - count
+            );
+        }
+
+        self.write(start * size_of::<T>(), &data[..count]);
+    }
+
+    /// Writes raw values at a byte offset, to change a single member in place
+    pub fn write<F: Copy>(&self, offset: usize, data: &[F]) {
+        if offset > self.size() {
+            log::warn!(
+                "buffer {} write past its {} bytes",
+                self.buf.id,
+                self.size()
+            );
+            return;
+        }
+
+        let size = core::mem::size_of_val(data).min(self.size() - offset);
+        unsafe {
+            gl::BindBuffer(L::TARGET, self.buf.id);
+            gl::BufferSubData(L::TARGET, offset as _, size as _, data.as_ptr() as _);
+        }
+    }
+}
+
+impl<T: Copy> BlockBuffer<T, Std430> {
+    /// Reads back what shaders wrote, from `start` on
+    pub fn read(&self, start: usize, out: &mut [T]) -> usize {
+        let count = out.len().min(self.len().saturating_sub(start));
+        unsafe {
+            gl::MemoryBarrier(gl::BUFFER_UPDATE_BARRIER_BIT);
+            gl::BindBuffer(gl::SHADER_STORAGE_BUFFER, self.buf.id);
+            gl::GetBufferSubData(
+                gl::SHADER_STORAGE_BUFFER,
+                (start * size_of::<T>()) as _,
+                (count * size_of::<T>()) as _,
+                out.as_mut_ptr() as _,
+            );
+        }
+
+        count
+    }
+}
+
+impl<T, L> Drop for BlockBuffer<T, L> {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.buf.id);
+        }
+    }
+}
+
+fn offset_alignment(target: GLenum) -> usize {
+    let pname = match target {
+        gl::UNIFORM_BUFFER => gl::UNIFORM_BUFFER_OFFSET_ALIGNMENT,
+        _ => gl::SHADER_STORAGE_BUFFER_OFFSET_ALIGNMENT,
+    };
+    let mut align = 0;
+    unsafe {
+        gl::GetIntegerv(pname, &mut align);
+    }
+
+    align.max(1) as usize
+}
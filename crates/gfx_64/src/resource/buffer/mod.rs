@@ -1,10 +1,16 @@
-use crate::{gl, gl::types::*, resource::Resource, GfxSystem};
+mod block;
+
+use crate::{gl, gl::types::*, resource::Resource};
+pub use block::{BlockBuffer, Layout, Std140, Std430, StorageBuffer, UniformBuffer};
 
 #[derive(Clone, Copy, Debug)]
 pub struct Buffer {
     pub(crate) id: GLuint,
     pub(crate) buf_type: GLenum,
+    pub(crate) usage: Usage,
     pub(crate) len: usize,
+    pub(crate) stride: usize,
+    pub(crate) capacity: usize,
 }
 
 impl Buffer {
@@ -14,32 +20,67 @@ impl Buffer {
             gl::GenBuffers(1, &mut id);
             gl::BindBuffer(buf_type, id);
 
-            gl::BufferData(
-                buf_type,
-                (core::mem::size_of::<Data>() * data.len()) as _,
-                data.as_ptr() as _,
-                usage as _,
-            );
+            let size = core::mem::size_of_val(data);
+            gl::BufferData(buf_type, size as _, data.as_ptr() as _, usage as _);
 
             Buffer {
                 buf_type,
                 id,
+                usage,
                 len: data.len(),
+                stride: core::mem::size_of::<Data>(),
+                capacity: size,
             }
         }
     }
 
     pub fn update<Data>(&mut self, data: &[Data]) {
         self.bind();
+        let size = core::mem::size_of_val(data);
         unsafe {
-            gl::BufferSubData(
-                self.buf_type,
-                0,
-                (core::mem::size_of::<Data>() * data.len()) as _,
-                data.as_ptr() as _,
-            );
+            if size > self.capacity {
+                log::trace!("growing buffer {} to {} bytes", self.id, size);
+                gl::BufferData(
+                    self.buf_type,
+                    size as _,
+                    data.as_ptr() as _,
+                    self.usage as _,
+                );
+                self.capacity = size;
+            } else {
+                gl::BufferSubData(self.buf_type, 0, size as _, data.as_ptr() as _);
+            }
         }
         self.len = data.len();
+        self.stride = core::mem::size_of::<Data>();
+    }
+
+    /// Overwrites elements from `start` on without touching the rest, never grows
+    pub fn update_range<Data>(&mut self, start: usize, data: &[Data]) {
+        let stride = core::mem::size_of::<Data>();
+        let offset = start * stride;
+        let size = core::mem::size_of_val(data).min(self.capacity.saturating_sub(offset));
+        if size < core::mem::size_of_val(data) {
+            log::warn!(
+                "buffer {} range update past its {} bytes",
+                self.id,
+                self.capacity
+            );
+        }
+
+        self.bind();
+        unsafe {
+            gl::BufferSubData(self.buf_type, offset as _, size as _, data.as_ptr() as _);
+        }
+        self.len = self.len.max(start + size / stride.max(1));
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
     }
 }
 
@@ -50,8 +91,15 @@ impl Resource for Buffer {
 }
 
 #[repr(u32)]
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug)]
 pub enum Usage {
     StaticDraw = gl::STATIC_DRAW,
+    StaticRead = gl::STATIC_READ,
+    StaticCopy = gl::STATIC_COPY,
+    DynamicDraw = gl::DYNAMIC_DRAW,
+    DynamicRead = gl::DYNAMIC_READ,
+    DynamicCopy = gl::DYNAMIC_COPY,
     StreamDraw = gl::STREAM_DRAW,
+    StreamRead = gl::STREAM_READ,
+    StreamCopy = gl::STREAM_COPY,
 }
@@ -0,0 +1,107 @@
+use crate::{
+    gl,
+    gl::types::*,
+    resource::buffer::{Buffer, Usage},
+};
+use base_64::mem::Vec;
+
+/// An index element type. Its largest value restarts the primitive
+pub trait Index: Copy + PartialEq {
+    const TYPE: GLenum;
+    const RESTART: Self;
+}
+
+impl Index for u8 {
+    const TYPE: GLenum = gl::UNSIGNED_BYTE;
+    const RESTART: Self = u8::MAX;
+}
+
+impl Index for u16 {
+    const TYPE: GLenum = gl::UNSIGNED_SHORT;
+    const RESTART: Self = u16::MAX;
+}
+
+impl Index for u32 {
+    const TYPE: GLenum = gl::UNSIGNED_INT;
+    const RESTART: Self = u32::MAX;
+}
+
+/// An element buffer, remembering its element type and whether any primitive restarts.
+/// Deletes the buffer when dropped, so it can't be copied
+#[derive(Debug)]
+pub struct Indices {
+    pub(crate) buffer: Buffer,
+    pub(crate) ty: GLenum,
+    pub(crate) restart: bool,
+}
+
+impl Indices {
+    pub fn new<I: Index>(indices: &[I], usage: Usage) -> Self {
+        detach();
+        Self {
+            buffer: Buffer::new(gl::ELEMENT_ARRAY_BUFFER, usage, indices),
+            ty: I::TYPE,
+            restart: indices.contains(&I::RESTART),
+        }
+    }
+
+    /// Stores `indices` in the smallest type that holds them, `u32::MAX` still restarts
+    pub fn compact(indices: &[u32], usage: Usage) -> Self {
+        let max = indices
+            .iter()
+            .filter(|&&idx| idx != u32::RESTART)
+            .max()
+            .copied()
+            .unwrap_or(0);
+
+        if max < u8::RESTART as u32 {
+            Self::new(&narrow::<u8>(indices), usage)
+        } else if max < u16::RESTART as u32 {
+            Self::new(&narrow::<u16>(indices), usage)
+        } else {
+            Self::new(indices, usage)
+        }
+    }
+
+    /// Replaces the contents, switching type if `I` differs
+    pub fn update<I: Index>(&mut self, indices: &[I]) {
+        detach();
+        self.buffer.update(indices);
+        self.ty = I::TYPE;
+        self.restart = indices.contains(&I::RESTART);
+    }
+
+    pub fn len(&self) -> usize {
+        self.buffer.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.buffer.len == 0
+    }
+
+    /// Byte offset of the `idx`th index
+    pub(crate) fn offset(&self, idx: usize) -> *const GLvoid {
+        (idx * self.buffer.stride) as _
+    }
+}
+
+impl Drop for Indices {
+    fn drop(&mut self) {
+        unsafe {
+            gl::DeleteBuffers(1, &self.buffer.id);
+        }
+    }
+}
+
+// The element array binding is part of the bound vertex array, so binding the buffer while
+// some mesh's is bound would swap that mesh's indices. Only `Mesh::new` attaches it
+fn detach() {
+    unsafe { gl::BindVertexArray(0) }
+}
+
+fn narrow<I: Index + TryFrom<u32>>(indices: &[u32]) -> Vec<I> {
+    indices
+        .iter()
+        .map(|&idx| I::try_from(idx).unwrap_or(I::RESTART))
+        .collect()
+}
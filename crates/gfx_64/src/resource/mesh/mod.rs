@@ -1,3 +1,4 @@
+mod index;
 mod vertex;
 
 pub use crate::resource::buffer::Usage;
@@ -5,13 +6,16 @@ use crate::{
     gl,
     gl::types::*,
     resource::{buffer::Buffer, Draw, Resource},
-    GfxSystem, Stencil,
+    Stencil,
 };
-pub use vertex::Vertex;
+use core::ops::Range;
+pub use index::{Index, Indices};
+pub use vertex::{bind_attribute, Attribute, Norm, Vertex, F16};
 
 pub type MeshId = GLuint;
 
-#[derive(Clone, Debug)]
+/// Owns its vertex array and buffers, so it can't be cloned
+#[derive(Debug)]
 pub struct Mesh {
     pub(crate) id: MeshId,
     pub(crate) vertices: Buffer,
@@ -19,7 +23,8 @@ pub struct Mesh {
 }
 
 impl Mesh {
-    pub fn new<V: Vertex>(verts: &[V], usage: Usage, topology: Topology) -> Mesh {
+    /// Takes a `Primitive` to draw the vertices in order, or a `Topology` to index them
+    pub fn new<V: Vertex>(verts: &[V], usage: Usage, topology: impl Into<Topology>) -> Mesh {
         let mut id = 0;
         unsafe {
             gl::GenVertexArrays(1, &mut id);
@@ -28,56 +33,92 @@ impl Mesh {
         }
         log::info!("mesh {:?} created", id);
 
-        match &topology {
-            Topology::TriIndexed(indices) => {
-                indices.bind();
-            }
-            _ => {}
+        let topology = topology.into();
+        if let Topology::Indexed(_, indices) = &topology {
+            indices.buffer.bind();
         }
 
         let vertices = Buffer::new(gl::ARRAY_BUFFER, usage, verts);
         V::bind();
 
-        let mesh = Mesh {
+        Mesh {
             id,
             vertices,
             topology,
+        }
+    }
+
+    pub fn update<V: Vertex>(&mut self, verts: &[V]) {
+        self.bind();
+        self.vertices.update(verts);
+    }
+
+    pub fn update_indices<I: Index>(&mut self, indices: &[I]) {
+        match &mut self.topology {
+            Topology::Indexed(_, buf) => buf.update(indices),
+            _ => log::warn!("mesh {} has no index buffer", self.id),
+        }
+    }
+
+    /// Draws the `range` of indices, or of vertices if the mesh has no indices
+    pub fn draw_range(&self, range: Range<usize>) {
+        self.bind();
+        let count = range.len() as GLsizei;
+        match &self.topology {
+            Topology::Arrays(primitive) => unsafe {
+                primitive.apply();
+                gl::DrawArrays(primitive.to_gl(), range.start as _, count);
+            },
+            Topology::Indexed(primitive, indices) => unsafe {
+                primitive.apply();
+                restart(indices.restart);
+                gl::DrawRangeElements(
+                    primitive.to_gl(),
+                    0,
+                    self.vertices.len.saturating_sub(1) as _,
+                    count,
+                    indices.ty,
+                    indices.offset(range.start),
+                );
+                restart(false);
+            },
+        }
+    }
+
+    /// Draws the `range` of indices with `base` added to each, so several meshes can share
+    /// one index buffer
+    pub fn draw_base_vertex(&self, range: Range<usize>, base: i32) {
+        let (primitive, indices) = match &self.topology {
+            Topology::Indexed(primitive, indices) => (primitive, indices),
+            Topology::Arrays(_) => {
+                let start = (range.start as i32 + base).max(0) as usize;
+                return self.draw_range(start..start + range.len());
+            }
         };
-        mesh
+
+        self.bind();
+        unsafe {
+            primitive.apply();
+            restart(indices.restart);
+            gl::DrawElementsBaseVertex(
+                primitive.to_gl(),
+                range.len() as _,
+                indices.ty,
+                indices.offset(range.start),
+                base,
+            );
+            restart(false);
+        }
     }
 }
 
 impl Draw for Mesh {
     fn draw(&self) {
-        unsafe {
-            self.bind();
-            match &self.topology {
-                Topology::TriIndexed(indices) => {
-                    gl::DrawElements(
-                        gl::TRIANGLES,
-                        indices.len as _,
-                        gl::UNSIGNED_BYTE,
-                        core::ptr::null(),
-                    );
-                }
-
-                Topology::Lines => {
-                    gl::DrawArrays(gl::LINE_STRIP, 0, self.vertices.len as _);
-                }
-
-                Topology::Points => {
-                    gl::DrawArrays(gl::POINTS, 0, self.vertices.len as _);
-                }
-
-                Topology::TriFan => {
-                    gl::DrawArrays(gl::TRIANGLE_FAN, 0, self.vertices.len as _);
-                }
-
-                Topology::TriStrip => {
-                    gl::DrawArrays(gl::TRIANGLE_STRIP, 0, self.vertices.len as _);
-                }
-            }
-        }
+        let len = match &self.topology {
+            Topology::Arrays(_) => self.vertices.len,
+            Topology::Indexed(_, indices) => indices.len(),
+        };
+        self.draw_range(0..len);
     }
 }
 
@@ -93,21 +134,89 @@ impl Drop for Mesh {
     fn drop(&mut self) {
         unsafe {
             gl::DeleteVertexArrays(1, &self.id);
+            gl::DeleteBuffers(1, &self.vertices.id);
         }
     }
 }
 
-#[derive(Clone, Copy, Debug)]
-pub enum Topology {
+fn restart(enable: bool) {
+    unsafe {
+        if enable {
+            gl::Enable(gl::PRIMITIVE_RESTART_FIXED_INDEX);
+        } else {
+            gl::Disable(gl::PRIMITIVE_RESTART_FIXED_INDEX);
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Primitive {
     Points,
     Lines,
-    TriFan,
+    LineStrip,
+    LineLoop,
+    Triangles,
     TriStrip,
-    TriIndexed(Buffer),
+    TriFan,
+    LinesAdjacency,
+    LineStripAdjacency,
+    TrianglesAdjacency,
+    TriStripAdjacency,
+    /// For tessellation, with this many vertices a patch
+    Patches(u8),
+}
+
+impl Primitive {
+    pub fn to_gl(self) -> GLenum {
+        match self {
+            Primitive::Points => gl::POINTS,
+            Primitive::Lines => gl::LINES,
+            Primitive::LineStrip => gl::LINE_STRIP,
+            Primitive::LineLoop => gl::LINE_LOOP,
+            Primitive::Triangles => gl::TRIANGLES,
+            Primitive::TriStrip => gl::TRIANGLE_STRIP,
+            Primitive::TriFan => gl::TRIANGLE_FAN,
+            Primitive::LinesAdjacency => gl::LINES_ADJACENCY,
+            Primitive::LineStripAdjacency => gl::LINE_STRIP_ADJACENCY,
+            Primitive::TrianglesAdjacency => gl::TRIANGLES_ADJACENCY,
+            Primitive::TriStripAdjacency => gl::TRIANGLE_STRIP_ADJACENCY,
+            Primitive::Patches(_) => gl::PATCHES,
+        }
+    }
+
+    fn apply(self) {
+        if let Primitive::Patches(verts) = self {
+            unsafe { gl::PatchParameteri(gl::PATCH_VERTICES, verts as _) }
+        }
+    }
+}
+
+/// Owns the index buffer when indexed, handing it to the mesh built from it
+#[derive(Debug)]
+pub enum Topology {
+    /// Vertices drawn in order
+    Arrays(Primitive),
+    Indexed(Primitive, Indices),
 }
 
 impl Topology {
-    pub fn from_index_buffer(buf: Buffer) -> Self {
-        Self::TriIndexed(buf)
+    /// Indexes with the smallest index type that fits, see `Indices::compact`
+    pub fn from_indices(primitive: Primitive, indices: &[u32]) -> Self {
+        Self::Indexed(primitive, Indices::compact(indices, Usage::StaticDraw))
+    }
+
+    pub fn primitive(&self) -> Primitive {
+        match self {
+            Topology::Arrays(primitive) | Topology::Indexed(primitive, _) => *primitive,
+        }
     }
 }
+
+impl From<Primitive> for Topology {
+    fn from(primitive: Primitive) -> Self {
+        Topology::Arrays(primitive)
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,34 @@
+use super::{Mesh, Primitive, Topology, Usage};
+use crate::{
+    resource::shader::{POS2D, WHITE},
+    Draw, GfxSystem, Pipeline, Resource,
+};
+
+const LEFT_HALF: [[f32; 2]; 4] = [[-1.0, -1.0], [0.0, -1.0], [-1.0, 1.0], [0.0, 1.0]];
+
+#[test]
+#[ignore = "needs SDL2 and a GL driver"]
+fn index_buffers_stay_with_their_mesh() {
+    let gfx = GfxSystem::headless(b"mesh test\0", 4, 4).unwrap();
+    let pipeline = Pipeline::new(POS2D, WHITE).unwrap();
+
+    let first = Mesh::new(
+        &LEFT_HALF,
+        Usage::StaticDraw,
+        Topology::from_indices(Primitive::Triangles, &[0, 1, 2, 1, 3, 2]),
+    );
+    // Degenerate, so the first mesh draws nothing if it ends up reading these
+    let mut second = Mesh::new(
+        &LEFT_HALF,
+        Usage::StaticDraw,
+        Topology::from_indices(Primitive::Triangles, &[0; 6]),
+    );
+    second.update_indices(&[0u8; 6]);
+
+    let image = gfx.render(|| {
+        pipeline.bind();
+        first.draw();
+    });
+    assert_eq!(image.pixel([0, 0]), [255; 4]);
+    assert_eq!(image.pixel([3, 3]), [0, 0, 0, 255]);
+}
@@ -1,32 +1,99 @@
 use crate::{gl, gl::types::*};
-use core::mem::size_of;
+use core::mem::{offset_of, size_of};
 
-pub trait Vertex: Sized {
-    const NUM: i32 = 0;
-    const SIZE: i32 = size_of::<Self>() as _;
+/// The GL format of one vertex attribute, i.e. one field of a vertex
+pub trait Attribute: Copy {
+    const NUM: i32 = 1;
     const TYPE: GLenum = gl::FLOAT;
+    /// Fixed point read as a float in [0, 1], or [-1, 1] if signed
     const NORM: bool = false;
+    /// Read as an `int`/`uint` by the shader instead of converted to float
+    const INTEGER: bool = false;
+}
 
-    fn bind() {
-        Self::bind_parameters(0, Self::NUM, Self::TYPE, Self::NORM, Self::SIZE, 0);
+/// Binds its attributes to consecutive locations from 0, in field order. Implemented for
+/// attributes, tuples of up to 6 attributes and, with `#[derive(Vertex)]`, `#[repr(C)]`
+/// structs of attributes
+pub trait Vertex: Sized {
+    fn bind();
+}
+
+/// Points attribute `idx` at an `A` found `offset` bytes into every `stride` bytes
+pub fn bind_attribute<A: Attribute>(idx: u32, stride: usize, offset: usize) {
+    unsafe {
+        gl::EnableVertexAttribArray(idx);
+        if A::INTEGER {
+            gl::VertexAttribIPointer(idx, A::NUM, A::TYPE, stride as _, offset as _);
+        } else {
+            gl::VertexAttribPointer(idx, A::NUM, A::TYPE, A::NORM as _, stride as _, offset as _);
+        }
     }
+}
+
+/// Half precision float bits
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct F16(pub u16);
 
-    fn bind_parameters(idx: u32, num: i32, _type: GLenum, norm: bool, size: i32, stride: i32) {
-        unsafe {
-            gl::EnableVertexAttribArray(idx);
+/// Normalizes a fixed point attribute, e.g. `Norm<[u8; 4]>` for an 8 bit color
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Norm<A>(pub A);
 
-            gl::VertexAttribPointer(idx, num, _type, norm as _, size, stride as _);
+impl<A: Attribute> Attribute for Norm<A> {
+    const NUM: i32 = A::NUM;
+    const TYPE: GLenum = A::TYPE;
+    const NORM: bool = true;
+}
+
+macro_rules! attribute {
+    ($($ty:ty => $gl:expr, $integer:expr;)*) => {$(
+        impl Attribute for $ty {
+            const TYPE: GLenum = $gl;
+            const INTEGER: bool = $integer;
         }
-    }
+
+        /// GL takes 1 to 4 components, anything else fails to build once it's bound
+        impl<const N: usize> Attribute for [$ty; N] {
+            const NUM: i32 = {
+                assert!(N >= 1 && N <= 4, "attribute arrays hold 1 to 4 components");
+                N as _
+            };
+            const TYPE: GLenum = $gl;
+            const INTEGER: bool = $integer;
+        }
+    )*};
 }
 
-impl<V1: Vertex, V2: Vertex> Vertex for (V1, V2) {
+attribute! {
+    f32 => gl::FLOAT, false;
+    F16 => gl::HALF_FLOAT, false;
+    u8 => gl::UNSIGNED_BYTE, true;
+    i8 => gl::BYTE, true;
+    u16 => gl::UNSIGNED_SHORT, true;
+    i16 => gl::SHORT, true;
+    u32 => gl::UNSIGNED_INT, true;
+    i32 => gl::INT, true;
+}
+
+impl<A: Attribute> Vertex for A {
     fn bind() {
-        V1::bind_parameters(0, V1::NUM, V1::TYPE, V1::NORM, Self::SIZE, 0);
-        V2::bind_parameters(1, V2::NUM, V2::TYPE, V2::NORM, Self::SIZE, V1::SIZE);
+        bind_attribute::<A>(0, size_of::<A>(), 0);
     }
 }
 
-impl<const N: usize> Vertex for [f32; N] {
-    const NUM: i32 = N as _;
+macro_rules! tuple {
+    ($($name:ident $idx:tt),*) => {
+        impl<$($name: Attribute),*> Vertex for ($($name,)*) {
+            fn bind() {
+                $(bind_attribute::<$name>($idx, size_of::<Self>(), offset_of!(Self, $idx));)*
+            }
+        }
+    };
 }
+
+tuple!(A 0, B 1);
+tuple!(A 0, B 1, C 2);
+tuple!(A 0, B 1, C 2, D 3);
+tuple!(A 0, B 1, C 2, D 3, E 4);
+tuple!(A 0, B 1, C 2, D 3, E 4, F 5);
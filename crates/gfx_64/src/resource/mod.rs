@@ -1,3 +1,4 @@
+pub mod blend;
 pub mod buffer;
 pub mod framebuffer;
 pub mod mesh;
@@ -6,7 +7,7 @@ pub mod shader;
 pub mod texture;
 pub mod window;
 
-use crate::{gl};
+use crate::{gl, gl::types::GLenum, Image};
 use base_64::math::Matrix;
 
 pub trait Resource {
@@ -15,14 +16,130 @@ pub trait Resource {
 
 pub trait Uniform {
     fn bind(&self, location: i32);
+
+    /// Whether this can be uploaded to a uniform of reflected GLSL type `ty`
+    fn accepts(&self, ty: GLenum) -> bool;
+
+    /// Array elements uploaded by `bind`
+    fn count(&self) -> i32 {
+        1
+    }
 }
 
-impl Uniform for Matrix<4, 4> {
-    fn bind(&self, location: i32) {
-        unsafe {
-            gl::UniformMatrix4fv(location, 1, gl::FALSE, self.as_ptr() as _);
+// Each type also works as a slice, for uniform arrays
+macro_rules! uniform {
+    ($ty:ty, $accepts:expr, |$location:ident, $count:ident, $ptr:ident| $upload:expr) => {
+        impl Uniform for $ty {
+            fn bind(&self, $location: i32) {
+                let ($count, $ptr) = (1, self as *const $ty);
+                unsafe { $upload }
+            }
+
+            fn accepts(&self, ty: GLenum) -> bool {
+                ($accepts)(ty)
+            }
         }
-    }
+
+        impl Uniform for [$ty] {
+            fn bind(&self, $location: i32) {
+                let ($count, $ptr) = (self.len() as i32, self.as_ptr());
+                unsafe { $upload }
+            }
+
+            fn accepts(&self, ty: GLenum) -> bool {
+                ($accepts)(ty)
+            }
+
+            fn count(&self) -> i32 {
+                self.len() as i32
+            }
+        }
+    };
+}
+
+uniform!(f32, |ty| ty == gl::FLOAT, |l, n, p| {
+    gl::Uniform1fv(l, n, p as _)
+});
+uniform!(i32, |ty| ty == gl::INT, |l, n, p| {
+    gl::Uniform1iv(l, n, p as _)
+});
+uniform!(u32, |ty| ty == gl::UNSIGNED_INT, |l, n, p| {
+    gl::Uniform1uiv(l, n, p as _)
+});
+uniform!([f32; 2], |ty| ty == gl::FLOAT_VEC2, |l, n, p| {
+    gl::Uniform2fv(l, n, p as _)
+});
+uniform!([f32; 3], |ty| ty == gl::FLOAT_VEC3, |l, n, p| {
+    gl::Uniform3fv(l, n, p as _)
+});
+uniform!([f32; 4], |ty| ty == gl::FLOAT_VEC4, |l, n, p| {
+    gl::Uniform4fv(l, n, p as _)
+});
+uniform!([i32; 2], |ty| ty == gl::INT_VEC2, |l, n, p| {
+    gl::Uniform2iv(l, n, p as _)
+});
+uniform!([i32; 4], |ty| ty == gl::INT_VEC4, |l, n, p| {
+    gl::Uniform4iv(l, n, p as _)
+});
+uniform!(Matrix<3, 3>, |ty| ty == gl::FLOAT_MAT3, |l, n, p| {
+    gl::UniformMatrix3fv(l, n, gl::FALSE, p as _)
+});
+uniform!(Matrix<4, 4>, |ty| ty == gl::FLOAT_MAT4, |l, n, p| {
+    gl::UniformMatrix4fv(l, n, gl::FALSE, p as _)
+});
+
+/// The texture unit a sampler uniform reads from
+#[repr(transparent)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Sampler(pub i32);
+
+uniform!(Sampler, is_sampler, |l, n, p| gl::Uniform1iv(l, n, p as _));
+
+/// Every GLSL sampler type, float, shadow, signed and unsigned
+fn is_sampler(ty: GLenum) -> bool {
+    matches!(
+        ty,
+        gl::SAMPLER_1D
+            | gl::SAMPLER_2D
+            | gl::SAMPLER_3D
+            | gl::SAMPLER_CUBE
+            | gl::SAMPLER_2D_RECT
+            | gl::SAMPLER_1D_ARRAY
+            | gl::SAMPLER_2D_ARRAY
+            | gl::SAMPLER_CUBE_MAP_ARRAY
+            | gl::SAMPLER_BUFFER
+            | gl::SAMPLER_2D_MULTISAMPLE
+            | gl::SAMPLER_2D_MULTISAMPLE_ARRAY
+            | gl::SAMPLER_1D_SHADOW
+            | gl::SAMPLER_2D_SHADOW
+            | gl::SAMPLER_CUBE_SHADOW
+            | gl::SAMPLER_2D_RECT_SHADOW
+            | gl::SAMPLER_1D_ARRAY_SHADOW
+            | gl::SAMPLER_2D_ARRAY_SHADOW
+            | gl::SAMPLER_CUBE_MAP_ARRAY_SHADOW
+            | gl::INT_SAMPLER_1D
+            | gl::INT_SAMPLER_2D
+            | gl::INT_SAMPLER_3D
+            | gl::INT_SAMPLER_CUBE
+            | gl::INT_SAMPLER_2D_RECT
+            | gl::INT_SAMPLER_1D_ARRAY
+            | gl::INT_SAMPLER_2D_ARRAY
+            | gl::INT_SAMPLER_CUBE_MAP_ARRAY
+            | gl::INT_SAMPLER_BUFFER
+            | gl::INT_SAMPLER_2D_MULTISAMPLE
+            | gl::INT_SAMPLER_2D_MULTISAMPLE_ARRAY
+            | gl::UNSIGNED_INT_SAMPLER_1D
+            | gl::UNSIGNED_INT_SAMPLER_2D
+            | gl::UNSIGNED_INT_SAMPLER_3D
+            | gl::UNSIGNED_INT_SAMPLER_CUBE
+            | gl::UNSIGNED_INT_SAMPLER_2D_RECT
+            | gl::UNSIGNED_INT_SAMPLER_1D_ARRAY
+            | gl::UNSIGNED_INT_SAMPLER_2D_ARRAY
+            | gl::UNSIGNED_INT_SAMPLER_CUBE_MAP_ARRAY
+            | gl::UNSIGNED_INT_SAMPLER_BUFFER
+            | gl::UNSIGNED_INT_SAMPLER_2D_MULTISAMPLE
+            | gl::UNSIGNED_INT_SAMPLER_2D_MULTISAMPLE_ARRAY
+    )
 }
 
 pub trait Draw {
@@ -50,7 +167,7 @@ pub trait Stencil: Draw {
     }
 }
 
-pub trait RenderTarget {
+pub trait RenderTarget: Resource {
     fn clear_color(&self, [r, g, b, a]: [f32; 4]) {
         unsafe {
             gl::ClearColor(r, g, b, a);
@@ -70,4 +187,26 @@ pub trait RenderTarget {
             gl::Viewport(x, y, w, h);
         }
     }
+
+    fn read_pixels(&self, [x, y]: [i32; 2], [w, h]: [i32; 2]) -> Image {
+        log::trace!("reading pixels: [{}, {}], [{}, {}]", x, y, w, h);
+        let mut image = Image::new([w as usize, h as usize]);
+        self.bind();
+        unsafe {
+            gl::PixelStorei(gl::PACK_ALIGNMENT, 1);
+            gl::ReadPixels(
+                x,
+                y,
+                w,
+                h,
+                gl::RGBA,
+                gl::UNSIGNED_BYTE,
+                image.pixels.as_mut_ptr() as _,
+            );
+        }
+
+        // GL rows run bottom to top
+        image.flip_rows();
+        image
+    }
 }
@@ -1,46 +1,238 @@
-use crate::{gl, gl::types::*};
-use crate::{resource::Resource, Shader};
+use crate::{
+    error::{Diagnostic, Phase},
+    gl,
+    gl::types::*,
+    resource::{
+        buffer::{BlockBuffer, Layout},
+        shader::{diagnostics, info_log},
+        Resource, Uniform,
+    },
+    Error, Shader, Source,
+};
+use base_64::mem::Vec;
+use core::cell::{Cell, Ref, RefCell};
+pub use reflect::{Block, Reflection, Variable};
+use sdl_64::Message;
 
-pub struct Pipeline(GLuint);
+mod reflect;
+#[cfg(feature = "edit")]
+mod watch;
+
+pub struct Pipeline {
+    // Swapped for a freshly linked program when a watched source changes
+    id: Cell<GLuint>,
+    reflection: RefCell<Reflection>,
+    #[cfg(feature = "edit")]
+    watch: Option<RefCell<watch::Watch>>,
+}
 
 impl Pipeline {
-    pub fn new(vert_src: &str, frag_src: &str) -> Pipeline {
-        let pipeline = unsafe { gl::CreateProgram() };
-        log::info!("creating pipeline {pipeline}");
+    pub fn new(vert: Source, frag: Source) -> Result<Pipeline, Error> {
+        Self::try_new(&[vert, frag])
+    }
 
-        let vert = Shader::new(vert_src, gl::VERTEX_SHADER);
-        let frag = Shader::new(frag_src, gl::FRAGMENT_SHADER);
-        vert.attach(pipeline);
-        frag.attach(pipeline);
+    /// Compiles every stage even after one fails, so the error lists all of them, then links
+    /// and validates. With `edit`, the sources' files are watched and the pipeline rebuilds
+    /// itself on the first bind after one changes
+    pub fn try_new(sources: &[Source]) -> Result<Pipeline, Error> {
+        #[allow(unused_mut)]
+        let mut pipeline = Self::build(sources)?;
+        #[cfg(feature = "edit")]
+        {
+            pipeline.watch = Some(RefCell::new(watch::Watch::new(sources)));
+        }
+
+        Ok(pipeline)
+    }
+
+    pub fn id(&self) -> GLuint {
+        self.id.get()
+    }
+
+    pub fn reflection(&self) -> Ref<'_, Reflection> {
+        self.reflection.borrow()
+    }
+
+    pub fn uniform(&self, name: &str) -> Option<Variable> {
+        self.reflection.borrow().uniform(name)
+    }
+
+    /// Uploads to the uniform called `name` of this pipeline, which must be bound. Like GL,
+    /// names the linker optimised out are ignored; debug builds also check `value` against the
+    /// uniform's GLSL type and array size
+    pub fn set<U: Uniform + ?Sized>(&self, name: &str, value: &U) -> Result<(), Error> {
+        let variable = match self.uniform(name) {
+            Some(variable) => variable,
+            None => return Ok(()),
+        };
+
+        if cfg!(debug_assertions) && (!value.accepts(variable.ty) || value.count() > variable.size)
+        {
+            log::error!(
+                "{} doesn't fit uniform '{}' of type {:#06x}",
+                core::any::type_name::<U>(),
+                name,
+                variable.ty
+            );
+            return Err(Error::UniformType {
+                name: Message::new(name.as_bytes()),
+                ty: variable.ty,
+            });
+        }
+
+        value.bind(variable.location);
+        Ok(())
+    }
+
+    /// Points the block called `name` at `buffer`'s binding point, checking the buffer is
+    /// big enough and that `T` is either the whole block or exactly one element of its array,
+    /// by the reflected size and stride. Blocks the linker dropped are ignored. Bindings made here are lost on reload under `edit`, give
+    /// the block a `layout(binding = n)` to keep it
+    pub fn bind_block<T: Copy, L: Layout>(
+        &self,
+        name: &str,
+        buffer: &BlockBuffer<T, L>,
+    ) -> Result<(), Error> {
+        let reflection = self.reflection.borrow();
+        let block = match L::INTERFACE {
+            gl::UNIFORM_BLOCK => reflection.uniform_block(name),
+            _ => reflection.storage_block(name),
+        };
+        let block = match block {
+            Some(block) => block,
+            None => return Ok(()),
+        };
+
+        let size = core::mem::size_of::<T>();
+        let element = block.stride > 0 && size == block.stride;
+        if buffer.size() < block.size || !(size == block.size || element) {
+            log::error!(
+                "{} doesn't match block '{}' of {} bytes and {} byte elements",
+                core::any::type_name::<T>(),
+                name,
+                block.size,
+                block.stride
+            );
+            return Err(Error::BlockSize {
+                name: Message::new(name.as_bytes()),
+                size,
+                expected: block.size,
+                stride: block.stride,
+            });
+        }
 
         unsafe {
-            gl::LinkProgram(pipeline);
+            match L::INTERFACE {
+                gl::UNIFORM_BLOCK => {
+                    gl::UniformBlockBinding(self.id(), block.index, buffer.binding())
+                }
+                _ => gl::ShaderStorageBlockBinding(self.id(), block.index, buffer.binding()),
+            }
+        }
+        Ok(())
+    }
+
+    fn build(sources: &[Source]) -> Result<Pipeline, Error> {
+        let mut pipeline = Self {
+            id: Cell::new(unsafe { gl::CreateProgram() }),
+            reflection: RefCell::default(),
+            #[cfg(feature = "edit")]
+            watch: None,
+        };
+        log::info!("creating pipeline {}", pipeline.id());
+
+        let mut shaders = Vec::with_capacity(sources.len());
+        let mut failures = Vec::new();
+        for source in sources {
+            match Shader::new(source) {
+                Ok(shader) => shaders.push(shader),
+                Err(Error::Shader(diagnostics)) => failures.extend(diagnostics),
+                Err(err) => return Err(err),
+            }
+        }
+        if !failures.is_empty() {
+            return Err(Error::Shader(failures));
         }
 
-        Self(pipeline)
+        for shader in shaders.iter() {
+            shader.attach(pipeline.id());
+        }
+        unsafe { gl::LinkProgram(pipeline.id()) }
+        if let Some(diagnostics) = pipeline.failure(gl::LINK_STATUS, Phase::Link) {
+            log::error!("pipeline {} failed to link", pipeline.id());
+            return Err(Error::Shader(diagnostics));
+        }
+
+        // Validation judges the program against the GL state of the moment, which isn't the
+        // state it will be drawn with, so a failure here is only worth a warning
+        unsafe { gl::ValidateProgram(pipeline.id()) }
+        if let Some(diagnostics) = pipeline.failure(gl::VALIDATE_STATUS, Phase::Validate) {
+            log::warn!("pipeline {} failed to validate", pipeline.id());
+            for diagnostic in diagnostics.iter() {
+                log::warn!("{}", diagnostic.message.as_str());
+            }
+        }
+        pipeline.reflection = RefCell::new(Reflection::new(pipeline.id()));
+
+        Ok(pipeline)
     }
-}
 
-impl Resource for Pipeline {
-    fn bind(&self) {
+    /// The program's log if `status` came back false
+    fn failure(&self, status: GLenum, phase: Phase) -> Option<Vec<Diagnostic>> {
+        let mut success = 1;
         unsafe {
-            gl::UseProgram(self.0);
+            gl::GetProgramiv(self.id(), status, &mut success);
+        }
+
+        (success == gl::FALSE as _).then(|| {
+            let log = info_log(self.id(), gl::GetProgramiv, gl::GetProgramInfoLog);
+            diagnostics(&log, phase, None)
+        })
+    }
+
+    /// Rebuilds from disk if a watched file changed; a broken edit keeps the old program
+    #[cfg(feature = "edit")]
+    fn reload(&self) {
+        let texts = match self
+            .watch
+            .as_ref()
+            .and_then(|watch| watch.borrow_mut().poll())
+        {
+            Some(texts) => texts,
+            None => return,
+        };
+
+        let sources: Vec<Source> = texts
+            .iter()
+            .map(|(stage, path, text)| Source::new(*stage, path, text))
+            .collect();
+        match Self::build(&sources) {
+            Ok(fresh) => {
+                log::info!("reloaded pipeline {} as {}", self.id(), fresh.id());
+                // The old program goes down with `fresh`
+                fresh.id.set(self.id.replace(fresh.id()));
+                self.reflection.swap(&fresh.reflection);
+            }
+            Err(err) => log::error!("keeping pipeline {}: {}", self.id(), err),
         }
     }
 }
 
-impl core::ops::Deref for Pipeline {
-    type Target = GLuint;
+impl Resource for Pipeline {
+    fn bind(&self) {
+        #[cfg(feature = "edit")]
+        self.reload();
 
-    fn deref(&self) -> &Self::Target {
-        &self.0
+        unsafe {
+            gl::UseProgram(self.id());
+        }
     }
 }
 
 impl Drop for Pipeline {
     fn drop(&mut self) {
         unsafe {
-            gl::DeleteProgram(self.0);
+            gl::DeleteProgram(self.id());
         }
     }
 }
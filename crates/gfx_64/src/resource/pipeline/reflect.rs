@@ -0,0 +1,300 @@
+use crate::{gl, gl::types::*};
+use base_64::mem::Vec;
+
+/// An active uniform or vertex input as the linker laid it out
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Variable {
+    pub location: i32,
+    /// GLSL type, e.g. `gl::FLOAT_VEC4`
+    pub ty: GLenum,
+    /// Element count, 1 unless it's an array
+    pub size: i32,
+}
+
+/// An active uniform or shader storage block
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Block {
+    pub index: GLuint,
+    /// Binding point it reads from, from `layout(binding = n)` or `Pipeline::bind_block`
+    pub binding: GLuint,
+    /// Bytes the block needs, for an unsized array that's the fixed part plus one element
+    pub size: usize,
+    /// Bytes between the elements of the array the block starts with, 0 if it starts with none
+    pub stride: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Entry<V> {
+    name: (usize, usize),
+    variable: V,
+}
+
+/// Active uniforms, attributes and blocks of a linked program, read once so lookups by name never
+/// go back to the driver
+#[derive(Debug, Default)]
+pub struct Reflection {
+    names: Vec<u8>,
+    uniforms: Vec<Entry<Variable>>,
+    attributes: Vec<Entry<Variable>>,
+    uniform_blocks: Vec<Entry<Block>>,
+    storage_blocks: Vec<Entry<Block>>,
+}
+
+impl Reflection {
+    pub(crate) fn new(program: GLuint) -> Self {
+        let mut reflection = Self::default();
+        reflection.uniforms = reflection.read(program, gl::UNIFORM);
+        reflection.attributes = reflection.read(program, gl::PROGRAM_INPUT);
+        reflection.uniform_blocks = reflection.read_blocks(program, gl::UNIFORM_BLOCK);
+        reflection.storage_blocks = reflection.read_blocks(program, gl::SHADER_STORAGE_BLOCK);
+        reflection
+    }
+
+    fn read(&mut self, program: GLuint, interface: GLenum) -> Vec<Entry<Variable>> {
+        self.read_with(program, interface, [gl::TYPE, gl::ARRAY_SIZE, gl::LOCATION])
+            .filter_map(|(name, [ty, size, location])| {
+                // Block members and built-ins have no location of their own
+                (location >= 0).then_some(Entry {
+                    name,
+                    variable: Variable {
+                        location,
+                        ty: ty as GLenum,
+                        size,
+                    },
+                })
+            })
+            .collect()
+    }
+
+    fn read_blocks(&mut self, program: GLuint, interface: GLenum) -> Vec<Entry<Block>> {
+        let mut blocks: Vec<Entry<Block>> = self
+            .read_with(
+                program,
+                interface,
+                [gl::BUFFER_BINDING, gl::BUFFER_DATA_SIZE],
+            )
+            .enumerate()
+            .map(|(index, (name, [binding, size]))| Entry {
+                name,
+                variable: Block {
+                    index: index as GLuint,
+                    binding: binding as GLuint,
+                    size: size as usize,
+                    stride: 0,
+                },
+            })
+            .collect();
+
+        // Only an array the block starts with is one `T` per element, anything else leaves
+        // the stride at 0 and `T` has to be the whole block
+        match interface {
+            gl::SHADER_STORAGE_BLOCK => {
+                let props = [gl::BLOCK_INDEX, gl::TOP_LEVEL_ARRAY_STRIDE, gl::OFFSET];
+                for (_, [block, stride, offset]) in resources(program, gl::BUFFER_VARIABLE, props) {
+                    if let Some(entry) = blocks.get_mut(block as usize) {
+                        if offset == 0 && stride > 0 {
+                            entry.variable.stride = stride as usize;
+                        }
+                    }
+                }
+            }
+            _ => {
+                // Uniforms only report strides of arrays of plain types, an array of structs
+                // has to be measured between the first members of elements 0 and 1
+                let props = [gl::BLOCK_INDEX, gl::ARRAY_STRIDE, gl::OFFSET];
+                let mut elements: Vec<[usize; 2]> =
+                    blocks.iter().map(|_| [usize::MAX; 2]).collect();
+                for (name, [block, stride, offset]) in resources(program, gl::UNIFORM, props) {
+                    let entry = match blocks.get_mut(block as usize) {
+                        Some(entry) => entry,
+                        None => continue,
+                    };
+                    let prefix = self.name(entry).as_bytes();
+                    match top_level_element(&name, prefix) {
+                        Some((0, false)) if offset == 0 => {
+                            entry.variable.stride = stride.max(0) as usize;
+                        }
+                        Some((element @ 0..=1, true)) => {
+                            let first = &mut elements[block as usize][element];
+                            *first = (*first).min(offset as usize);
+                        }
+                        _ => {}
+                    }
+                }
+
+                for (entry, [zero, one]) in blocks.iter_mut().zip(elements) {
+                    if zero == 0 && one != usize::MAX {
+                        entry.variable.stride = one;
+                    }
+                }
+            }
+        }
+
+        blocks
+    }
+
+    /// Every resource of `interface` with the given properties, names go in the arena
+    fn read_with<const N: usize>(
+        &mut self,
+        program: GLuint,
+        interface: GLenum,
+        props: [GLenum; N],
+    ) -> impl Iterator<Item = ((usize, usize), [GLint; N])> + '_ {
+        resources(program, interface, props).map(move |(name, values)| {
+            // Arrays are reported as `name[0]`, look them up by the bare name
+            let mut name = &name[..];
+            if let Some(bare) = name.strip_suffix(b"[0]") {
+                name = bare;
+            }
+
+            let start = self.names.len();
+            self.names.extend_from_slice(name);
+            ((start, name.len()), values)
+        })
+    }
+
+    fn name<V>(&self, entry: &Entry<V>) -> &str {
+        let (start, len) = entry.name;
+        core::str::from_utf8(&self.names[start..start + len]).unwrap_or("")
+    }
+
+    fn find<V: Copy>(&self, entries: &[Entry<V>], name: &str) -> Option<V> {
+        entries
+            .iter()
+            .find(|entry| self.name(entry) == name)
+            .map(|entry| entry.variable)
+    }
+
+    pub fn uniform(&self, name: &str) -> Option<Variable> {
+        self.find(&self.uniforms, name)
+    }
+
+    pub fn attribute(&self, name: &str) -> Option<Variable> {
+        self.find(&self.attributes, name)
+    }
+
+    pub fn uniform_block(&self, name: &str) -> Option<Block> {
+        self.find(&self.uniform_blocks, name)
+    }
+
+    pub fn storage_block(&self, name: &str) -> Option<Block> {
+        self.find(&self.storage_blocks, name)
+    }
+
+    pub fn uniforms(&self) -> impl Iterator<Item = (&str, Variable)> + '_ {
+        self.uniforms
+            .iter()
+            .map(move |entry| (self.name(entry), entry.variable))
+    }
+
+    pub fn attributes(&self) -> impl Iterator<Item = (&str, Variable)> + '_ {
+        self.attributes
+            .iter()
+            .map(move |entry| (self.name(entry), entry.variable))
+    }
+}
+
+/// Every resource of `interface` with its name and the given properties
+fn resources<const N: usize>(
+    program: GLuint,
+    interface: GLenum,
+    props: [GLenum; N],
+) -> impl Iterator<Item = (Vec<u8>, [GLint; N])> {
+    let mut count = 0;
+    unsafe {
+        gl::GetProgramInterfaceiv(program, interface, gl::ACTIVE_RESOURCES, &mut count);
+    }
+
+    (0..count.max(0) as GLuint).map(move |idx| {
+        let mut values = [0; N];
+        // Sized per resource, so long names come back whole
+        let mut len = 0;
+        unsafe {
+            gl::GetProgramResourceiv(
+                program,
+                interface,
+                idx,
+                1,
+                &gl::NAME_LENGTH,
+                1,
+                core::ptr::null_mut(),
+                &mut len,
+            );
+        }
+
+        let mut name: Vec<u8> = (0..len.max(1)).map(|_| 0).collect();
+        let mut written = 0;
+        unsafe {
+            gl::GetProgramResourceiv(
+                program,
+                interface,
+                idx,
+                N as _,
+                props.as_ptr(),
+                N as _,
+                core::ptr::null_mut(),
+                values.as_mut_ptr(),
+            );
+            gl::GetProgramResourceName(
+                program,
+                interface,
+                idx,
+                name.len() as _,
+                &mut written,
+                name.as_mut_ptr() as *mut GLchar,
+            );
+        }
+
+        name.truncate(written.max(0) as usize);
+        (name, values)
+    })
+}
+
+/// Element index of a top-level array member like `Block.lights[1].color` or `weights[0]`,
+/// and whether the array holds structs. `block` is the optional prefix GL puts on the name
+fn top_level_element(name: &[u8], block: &[u8]) -> Option<(usize, bool)> {
+    let name = name
+        .strip_prefix(block)
+        .and_then(|rest| rest.strip_prefix(b"."))
+        .unwrap_or(name);
+    let open = name.iter().position(|&c| c == b'[')?;
+    if name[..open].contains(&b'.') {
+        return None;
+    }
+
+    let rest = &name[open + 1..];
+    let close = rest.iter().position(|&c| c == b']')?;
+    let is_struct = match rest.get(close + 1) {
+        None => false,
+        Some(b'.') => true,
+        // An array of arrays, not something a `T` per element can match
+        Some(_) => return None,
+    };
+
+    let element = core::str::from_utf8(&rest[..close]).ok()?.parse().ok()?;
+    Some((element, is_struct))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::top_level_element;
+
+    #[test]
+    fn top_level_elements() {
+        assert_eq!(top_level_element(b"weights[0]", b"B"), Some((0, false)));
+        assert_eq!(top_level_element(b"B.weights[0]", b"B"), Some((0, false)));
+        assert_eq!(top_level_element(b"lights[1].color", b"B"), Some((1, true)));
+        assert_eq!(
+            top_level_element(b"Lights.lights[0].pos[0]", b"Lights"),
+            Some((0, true))
+        );
+    }
+
+    #[test]
+    fn nested_or_plain_members() {
+        assert_eq!(top_level_element(b"m", b"B"), None);
+        assert_eq!(top_level_element(b"light.weights[0]", b"B"), None);
+        assert_eq!(top_level_element(b"grid[0][1]", b"B"), None);
+        assert_eq!(top_level_element(b"C.weights[0]", b"B"), None);
+    }
+}
@@ -0,0 +1,89 @@
+use crate::{Source, Stage};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    string::String,
+    time::{Duration, Instant, SystemTime},
+    vec::Vec,
+};
+
+// Binds happen every frame, stat'ing files that often would be wasteful
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+struct File {
+    stage: Stage,
+    path: &'static str,
+    file: PathBuf,
+    modified: Option<SystemTime>,
+}
+
+/// Remembers where a pipeline's sources live on disk and notices when they change
+pub(crate) struct Watch {
+    files: Vec<File>,
+    last_poll: Instant,
+}
+
+impl Watch {
+    pub(crate) fn new(sources: &[Source]) -> Self {
+        let files = sources
+            .iter()
+            .map(|source| {
+                let file = source.file();
+                File {
+                    stage: source.stage,
+                    path: source.path,
+                    modified: modified(&file),
+                    file,
+                }
+            })
+            .collect();
+
+        Self {
+            files,
+            last_poll: Instant::now(),
+        }
+    }
+
+    /// Every stage's text, fresh from disk, if any of them changed since the last call
+    pub(crate) fn poll(&mut self) -> Option<Vec<(Stage, &'static str, String)>> {
+        if self.last_poll.elapsed() < POLL_INTERVAL {
+            return None;
+        }
+        self.last_poll = Instant::now();
+
+        // Only remembered once every file reads, so a half-saved edit is retried next poll
+        let stamps: Vec<Option<SystemTime>> =
+            self.files.iter().map(|file| modified(&file.file)).collect();
+        let changed = self
+            .files
+            .iter()
+            .zip(stamps.iter())
+            .any(|(file, &stamp)| stamp.is_some() && stamp != file.modified);
+        if !changed {
+            return None;
+        }
+
+        let mut texts = Vec::with_capacity(self.files.len());
+        for file in self.files.iter() {
+            match fs::read_to_string(&file.file) {
+                Ok(text) => texts.push((file.stage, file.path, text)),
+                Err(err) => {
+                    log::error!("can't reload {}: {}", file.file.display(), err);
+                    return None;
+                }
+            }
+        }
+
+        for (file, stamp) in self.files.iter_mut().zip(stamps) {
+            if stamp.is_some() {
+                file.modified = stamp;
+            }
+        }
+
+        Some(texts)
+    }
+}
+
+fn modified(file: &Path) -> Option<SystemTime> {
+    fs::metadata(file).and_then(|meta| meta.modified()).ok()
+}
@@ -1,14 +1,12 @@
-macro_rules! shader_src {
-    ($src:literal) => {
-        concat!(include_str!($src), "\0")
-    };
-}
+use super::Source;
+use crate::shader_source;
 
-pub const POS2D: &str = shader_src!("pos2d.vert");
-pub const POS2D_TEX2D: &str = shader_src!("pos2d_tex2d.vert");
-pub const POS3D: &str = shader_src!("pos3d.vert");
-pub const POS2D_RGB: &str = shader_src!("pos2d_rgb.vert");
-pub const POS3D_RGB: &str = shader_src!("pos3d_rgb.vert");
-pub const WHITE: &str = shader_src!("white.frag");
-pub const RGB: &str = shader_src!("rgb.frag");
-pub const TEX2D: &str = shader_src!("tex2d.frag");
+pub const POS2D: Source = shader_source!(Vertex, "src/resource/shader/defaults/pos2d.vert");
+pub const POS2D_TEX2D: Source =
+    shader_source!(Vertex, "src/resource/shader/defaults/pos2d_tex2d.vert");
+pub const POS3D: Source = shader_source!(Vertex, "src/resource/shader/defaults/pos3d.vert");
+pub const POS2D_RGB: Source = shader_source!(Vertex, "src/resource/shader/defaults/pos2d_rgb.vert");
+pub const POS3D_RGB: Source = shader_source!(Vertex, "src/resource/shader/defaults/pos3d_rgb.vert");
+pub const WHITE: Source = shader_source!(Fragment, "src/resource/shader/defaults/white.frag");
+pub const RGB: Source = shader_source!(Fragment, "src/resource/shader/defaults/rgb.frag");
+pub const TEX2D: Source = shader_source!(Fragment, "src/resource/shader/defaults/tex2d.frag");
@@ -1,42 +1,49 @@
 mod defaults;
+mod source;
 
-use crate::{gl, gl::types::*, Pipeline};
+use crate::{
+    error::{Diagnostic, Phase},
+    gl,
+    gl::types::*,
+    Error,
+};
+use base_64::mem::Vec;
 pub use defaults::*;
+use sdl_64::Message;
+pub use source::{Source, Stage};
 
 pub struct Shader(GLuint);
 
 impl Shader {
-    pub fn new(source: &str, stage: GLenum) -> Self {
+    pub fn new(source: &Source) -> Result<Self, Error> {
         unsafe {
-            let shader = gl::CreateShader(stage);
-            log::info!("building shader {}", shader);
+            let shader = Self(gl::CreateShader(source.stage.to_gl()));
+            log::info!(
+                "building {} shader {} from {}",
+                source.stage.name(),
+                shader.0,
+                source.path
+            );
 
-            gl::ShaderSource(shader, 1, &(source.as_ptr() as _), core::ptr::null());
-            gl::CompileShader(shader);
-            let err = gl::GetError();
-
-            log::info!("compile results: {}", err);
+            // Sized explicitly, so the text needs no nul
+            let len = source.text.len() as GLint;
+            gl::ShaderSource(shader.0, 1, &(source.text.as_ptr() as _), &len);
+            gl::CompileShader(shader.0);
 
             let mut success = 1;
-            gl::GetShaderiv(shader, gl::COMPILE_STATUS, &mut success);
+            gl::GetShaderiv(shader.0, gl::COMPILE_STATUS, &mut success);
+            let log = info_log(shader.0, gl::GetShaderiv, gl::GetShaderInfoLog);
+            let diagnostics = diagnostics(&log, Phase::Compile(source.stage), Some(source));
 
             if success == gl::FALSE as _ {
-                let mut log_len = 0;
-                gl::GetShaderiv(shader, gl::INFO_LOG_LENGTH, &mut log_len);
-                log::debug!("received {log_len} bytes err msg");
-
-                let mut err = [0u8; 150];
-                let mut len = 0;
-                gl::GetShaderInfoLog(shader, 150, &mut len, err.as_mut_ptr() as *mut GLchar);
-
-                log::error!(
-                    "received {len} bytes: {}",
-                    core::str::from_utf8(&err).unwrap()
-                );
-                panic!();
+                log::error!("{} failed to compile", source.path);
+                return Err(Error::Shader(diagnostics));
+            }
+            for diagnostic in diagnostics.iter() {
+                log::warn!("{}: {}", source.path, diagnostic.message.as_str());
             }
 
-            Self(shader)
+            Ok(shader)
         }
     }
 
@@ -55,3 +62,65 @@ impl Drop for Shader {
         }
     }
 }
+
+type GetIv = unsafe extern "system" fn(GLuint, GLenum, *mut GLint);
+type GetLog = unsafe extern "system" fn(GLuint, GLsizei, *mut GLsizei, *mut GLchar);
+
+/// The whole info log of a shader or program
+pub(crate) fn info_log(id: GLuint, get_iv: GetIv, get_log: GetLog) -> Vec<u8> {
+    let mut len = 0;
+    unsafe {
+        get_iv(id, gl::INFO_LOG_LENGTH, &mut len);
+    }
+
+    let mut log: Vec<u8> = (0..len.max(0)).map(|_| 0).collect();
+    let mut written = 0;
+    unsafe {
+        get_log(id, len, &mut written, log.as_mut_ptr() as *mut GLchar);
+    }
+    log.truncate(written.max(0) as usize);
+    log
+}
+
+/// Splits a log into lines, pointing each at the line of `source` it names
+pub(crate) fn diagnostics(log: &[u8], phase: Phase, source: Option<&Source>) -> Vec<Diagnostic> {
+    log.split(|&c| c == b'\n')
+        .map(|line| core::str::from_utf8(line).unwrap_or("").trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let source_line = source.and_then(|_| parse_line(line));
+            Diagnostic {
+                phase,
+                path: source.map_or("", |source| source.path),
+                line: source_line,
+                message: Message::new(line.as_bytes()),
+                code: source
+                    .zip(source_line)
+                    .and_then(|(source, n)| source.line(n))
+                    .map(|code| Message::new(code.trim().as_bytes())),
+            }
+        })
+        .collect()
+}
+
+// Drivers disagree on the format: Mesa writes `0:12(5): error`, NVIDIA `0(12) : error` and
+// AMD and Intel `ERROR: 0:12: `. All lead with the string index, then the line
+fn parse_line(line: &str) -> Option<u32> {
+    let line = line
+        .strip_prefix("ERROR: ")
+        .or_else(|| line.strip_prefix("WARNING: "))
+        .unwrap_or(line);
+    let rest = line.trim_start_matches(|c: char| c.is_ascii_digit());
+    if rest.len() == line.len() {
+        return None;
+    }
+
+    let rest = rest.strip_prefix(':').or_else(|| rest.strip_prefix('('))?;
+    let digits = rest
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(rest.len());
+    rest[..digits].parse().ok()
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,87 @@
+use crate::gl::{self, types::GLenum};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stage {
+    Vertex,
+    Fragment,
+    Geometry,
+    Compute,
+}
+
+impl Stage {
+    pub fn to_gl(self) -> GLenum {
+        match self {
+            Stage::Vertex => gl::VERTEX_SHADER,
+            Stage::Fragment => gl::FRAGMENT_SHADER,
+            Stage::Geometry => gl::GEOMETRY_SHADER,
+            Stage::Compute => gl::COMPUTE_SHADER,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Stage::Vertex => "vertex",
+            Stage::Fragment => "fragment",
+            Stage::Geometry => "geometry",
+            Stage::Compute => "compute",
+        }
+    }
+}
+
+/// GLSL text plus where it came from, so diagnostics can point back into the file
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Source<'a> {
+    pub stage: Stage,
+    pub path: &'static str,
+    pub text: &'a str,
+    // Only kept for reloading, so minsize builds don't carry absolute paths around
+    #[cfg(feature = "edit")]
+    root: &'static str,
+}
+
+impl<'a> Source<'a> {
+    /// `path` is only used in diagnostics, or relative to the working directory for reloads
+    pub const fn new(stage: Stage, path: &'static str, text: &'a str) -> Self {
+        Self::embedded(stage, "", path, text)
+    }
+
+    /// Text baked in from `path` under `root`, see `shader_source!`
+    #[allow(unused_variables)]
+    pub const fn embedded(
+        stage: Stage,
+        root: &'static str,
+        path: &'static str,
+        text: &'a str,
+    ) -> Self {
+        Self {
+            stage,
+            path,
+            text,
+            #[cfg(feature = "edit")]
+            root,
+        }
+    }
+
+    #[cfg(feature = "edit")]
+    pub fn file(&self) -> std::path::PathBuf {
+        std::path::Path::new(self.root).join(self.path)
+    }
+
+    /// Line `n` of the text, counting from 1 like GLSL does
+    pub fn line(&self, n: u32) -> Option<&'a str> {
+        self.text.lines().nth((n as usize).checked_sub(1)?)
+    }
+}
+
+/// Embeds a shader by its path relative to the calling crate's manifest
+#[macro_export]
+macro_rules! shader_source {
+    ($stage:ident, $path:literal) => {
+        $crate::resource::shader::Source::embedded(
+            $crate::resource::shader::Stage::$stage,
+            env!("CARGO_MANIFEST_DIR"),
+            $path,
+            include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/", $path)),
+        )
+    };
+}
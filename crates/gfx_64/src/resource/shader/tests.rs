@@ -0,0 +1,38 @@
+use super::parse_line;
+
+#[test]
+fn mesa() {
+    assert_eq!(parse_line("0:12(5): error: `foo' undeclared"), Some(12));
+    assert_eq!(parse_line("0:3(1): warning: unused variable"), Some(3));
+}
+
+#[test]
+fn nvidia() {
+    assert_eq!(
+        parse_line("0(12) : error C1008: undefined variable \"foo\""),
+        Some(12)
+    );
+    assert_eq!(
+        parse_line("0(7) : warning C7050: \"x\" might be used before being initialized"),
+        Some(7)
+    );
+}
+
+#[test]
+fn amd_intel() {
+    assert_eq!(
+        parse_line("ERROR: 0:12: 'foo' : undeclared identifier"),
+        Some(12)
+    );
+    assert_eq!(parse_line("WARNING: 0:4: extension not supported"), Some(4));
+}
+
+#[test]
+fn no_line() {
+    assert_eq!(parse_line("error: linking failed"), None);
+    assert_eq!(
+        parse_line("ERROR: 1 compilation errors.  No code generated."),
+        None
+    );
+    assert_eq!(parse_line(""), None);
+}
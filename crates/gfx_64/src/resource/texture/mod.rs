@@ -19,6 +19,13 @@ pub enum Format {
     Stencil = gl::STENCIL_INDEX,
 }
 
+#[repr(u32)]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Filter {
+    Nearest = gl::NEAREST,
+    Linear = gl::LINEAR,
+}
+
 impl Texture {
     pub fn new(target: Target, [w, h]: [i32; 2], format: Format) -> Texture {
         let mut id = 0;
@@ -43,6 +50,33 @@ impl Texture {
 
         Texture { id, target }
     }
+
+    pub fn upload(&self, [x, y]: [i32; 2], [w, h]: [i32; 2], format: Format, pixels: &[u8]) {
+        log::trace!("uploading {}x{} pixels to texture {}", w, h, self.id);
+        self.bind();
+        unsafe {
+            gl::PixelStorei(gl::UNPACK_ALIGNMENT, 1);
+            gl::TexSubImage2D(
+                self.target as _,
+                0,
+                x,
+                y,
+                w,
+                h,
+                format as u32,
+                gl::UNSIGNED_BYTE,
+                pixels.as_ptr() as _,
+            );
+        }
+    }
+
+    pub fn filter(&self, filter: Filter) {
+        self.bind();
+        unsafe {
+            gl::TexParameteri(self.target as _, gl::TEXTURE_MAG_FILTER, filter as _);
+            gl::TexParameteri(self.target as _, gl::TEXTURE_MIN_FILTER, filter as _);
+        }
+    }
 }
 
 impl Resource for Texture {
@@ -1,4 +1,4 @@
-pub use sdl_64::window::Window;
+pub use sdl_64::window::{Fullscreen, Position, SwapInterval, Window, WindowBuilder};
 
 use crate::{
     gl, {RenderTarget, Resource},
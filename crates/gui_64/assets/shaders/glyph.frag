@@ -0,0 +1,8 @@
+#version 460
+out vec4 frag_color;
+
+layout(location=1) uniform float weight;
+
+void main() {
+	frag_color = vec4(weight);
+}
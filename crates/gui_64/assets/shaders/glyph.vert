@@ -0,0 +1,8 @@
+#version 460
+layout(location=0) in vec2 pos;
+
+layout(location=0) uniform vec2 offset;
+
+void main() {
+	gl_Position = vec4(pos + offset, 0.0, 1.0);
+}
@@ -0,0 +1,42 @@
+#version 460
+in vec2 tex_coords;
+flat in vec4 uv_rect;
+
+out vec4 frag_color;
+
+uniform sampler2D tex;
+layout(location=1) uniform vec4 color;
+layout(location=2) uniform vec4 outline_color;
+layout(location=3) uniform float outline_width;
+layout(location=4) uniform vec4 glow_color;
+layout(location=5) uniform float glow_width;
+layout(location=6) uniform vec4 shadow_color;
+layout(location=7) uniform vec2 shadow_offset;
+
+vec4 premultiply(vec4 c) {
+	return vec4(c.rgb * c.a, c.a);
+}
+
+vec4 over(vec4 top, vec4 bottom) {
+	return top + bottom * (1.0 - top.a);
+}
+
+void main() {
+	// 0.5 is the glyph edge, positive inside
+	float dist = texture(tex, tex_coords).a - 0.5;
+	float aa = max(fwidth(dist), 0.0001) * 0.5;
+
+	float fill = smoothstep(-aa, aa, dist);
+	float outline = smoothstep(-aa, aa, dist + outline_width);
+	float glow = glow_width > 0.0 ? 1.0 - smoothstep(0.0, glow_width, -dist - outline_width) : 0.0;
+	// Stay inside this glyph's cell, half a texel in so filtering can't reach the neighbours
+	vec2 half_texel = 0.5 / vec2(textureSize(tex, 0));
+	vec2 shadow_coords = clamp(tex_coords - shadow_offset, uv_rect.xy + half_texel, uv_rect.zw - half_texel);
+	float shadow_dist = texture(tex, shadow_coords).a - 0.5;
+	float shadow = smoothstep(-aa, aa, shadow_dist + outline_width);
+
+	vec4 result = premultiply(shadow_color) * shadow;
+	result = over(premultiply(glow_color) * glow, result);
+	result = over(premultiply(outline_color) * outline, result);
+	frag_color = over(premultiply(color) * fill, result);
+}
@@ -4,7 +4,9 @@ in vec2 tex_coords;
 out vec4 frag_color;
 
 uniform sampler2D tex;
+layout(location=1) uniform vec4 color;
 
 void main() {
-	frag_color = texture(tex, tex_coords);
+	float coverage = texture(tex, tex_coords).a;
+	frag_color = vec4(color.rgb * color.a, color.a) * coverage;
 }
@@ -1,12 +1,15 @@
 #version 460
 layout(location=0) in vec2 pos;
 layout(location=1) in vec2 tex;
+layout(location=2) in vec4 cell;
 
 out vec2 tex_coords;
+flat out vec4 uv_rect;
 
 layout(location=0) uniform mat4 projection;
 
 void main() {
 	tex_coords = tex;
+	uv_rect = cell;
 	gl_Position = projection * vec4(pos, 0.0, 1.0);
 }
@@ -0,0 +1,72 @@
+use base_64::mem::Vec;
+use gfx_64::{
+    resource::mesh::{Indices, Mesh, Primitive, Topology, Usage},
+    Draw,
+};
+
+/// Position, texture coordinates and the glyph's whole atlas cell, which effects that sample
+/// around the glyph are clamped to
+pub type TextVertex = ([f32; 2], [f32; 2], [f32; 4]);
+
+pub struct TextBatch {
+    mesh: Mesh,
+    verts: Vec<TextVertex>,
+    // u32, as u16 would wrap past 16384 quads and hit the primitive restart index before that
+    indices: Vec<u32>,
+}
+
+impl TextBatch {
+    pub fn new() -> Self {
+        let indices = Indices::new::<u32>(&[], Usage::StreamDraw);
+        let mesh = Mesh::new::<TextVertex>(
+            &[],
+            Usage::StreamDraw,
+            Topology::Indexed(Primitive::Triangles, indices),
+        );
+
+        Self {
+            mesh,
+            verts: Vec::with_capacity(64),
+            indices: Vec::with_capacity(96),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.verts.clear();
+        self.indices.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    pub fn quad(&mut self, [left, bottom, right, top]: [f32; 4], uv: [f32; 4]) {
+        let base = self.verts.len() as u32;
+        let [u0, v0, u1, v1] = uv;
+        self.verts.extend([
+            ([left, top], [u0, v1], uv),
+            ([right, top], [u1, v1], uv),
+            ([left, bottom], [u0, v0], uv),
+            ([right, bottom], [u1, v0], uv),
+        ]);
+        self.indices
+            .extend([base, base + 2, base + 1, base + 1, base + 2, base + 3]);
+    }
+
+    pub fn flush(&mut self) {
+        if !self.is_empty() {
+            log::trace!("drawing {} batched glyphs", self.indices.len() / 6);
+            self.mesh.update(&self.verts);
+            self.mesh.update_indices(&self.indices);
+            self.mesh.draw();
+        }
+
+        self.clear();
+    }
+}
+
+impl Default for TextBatch {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,31 @@
+#[cfg(feature = "display")]
+use core::fmt;
+use ttf_parser::FaceParsingError;
+
+#[derive(Debug, Clone)]
+pub enum Error {
+    Gfx(gfx_64::Error),
+    Font(FaceParsingError),
+}
+
+impl From<gfx_64::Error> for Error {
+    fn from(err: gfx_64::Error) -> Self {
+        Error::Gfx(err)
+    }
+}
+
+impl From<FaceParsingError> for Error {
+    fn from(err: FaceParsingError) -> Self {
+        Error::Font(err)
+    }
+}
+
+#[cfg(feature = "display")]
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Gfx(err) => fmt::Display::fmt(err, f),
+            Error::Font(err) => write!(f, "couldn't parse font: {}", err),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use super::DistanceField;
+use gfx_64::{
+    resource::{
+        framebuffer::{Attachment, Framebuffer},
+        texture::{Filter, Format, Target, Texture},
+    },
+    RenderTarget, Resource,
+};
+
+const PADDING: i32 = 1;
+
+#[derive(Debug)]
+pub struct Packer {
+    dim: [i32; 2],
+    cursor: [i32; 2],
+    shelf: i32,
+}
+
+impl Packer {
+    pub fn new(dim: [i32; 2]) -> Self {
+        Self {
+            dim,
+            cursor: [0, 0],
+            shelf: 0,
+        }
+    }
+
+    pub fn pack(&mut self, [w, h]: [i32; 2]) -> Option<[i32; 2]> {
+        if w > self.dim[0] || h > self.dim[1] {
+            return None;
+        }
+
+        if self.cursor[0] + w > self.dim[0] {
+            self.cursor = [0, self.cursor[1] + self.shelf];
+            self.shelf = 0;
+        }
+
+        if self.cursor[1] + h > self.dim[1] {
+            return None;
+        }
+
+        let pos = self.cursor;
+        self.cursor[0] += w + PADDING;
+        self.shelf = self.shelf.max(h + PADDING);
+        Some(pos)
+    }
+}
+
+#[derive(Debug)]
+pub struct Atlas {
+    pub(crate) tex: Texture,
+    // Stencil target glyphs are rasterised into, distance fields are uploaded instead
+    target: Option<(Framebuffer, Texture)>,
+    packer: Packer,
+    dim: [i32; 2],
+}
+
+impl Atlas {
+    pub fn new(dim: [i32; 2], filter: Filter) -> Self {
+        log::debug!("creating {}x{} glyph atlas", dim[0], dim[1]);
+        let tex = Texture::new(Target::Tex2d, dim, Format::Rgba);
+        tex.filter(filter);
+        let stencil = Texture::new(Target::Tex2d, dim, Format::Stencil);
+        let fb = Framebuffer::new(
+            &[Attachment::Color0, Attachment::Stencil],
+            &[&tex, &stencil],
+        );
+        Self::clear(&fb, dim);
+
+        Self {
+            tex,
+            target: Some((fb, stencil)),
+            packer: Packer::new(dim),
+            dim,
+        }
+    }
+
+    pub fn sdf(dim: [i32; 2]) -> Self {
+        log::debug!("creating {}x{} distance field atlas", dim[0], dim[1]);
+        let tex = Texture::new(Target::Tex2d, dim, Format::Rgba);
+        tex.filter(Filter::Linear);
+        Self::clear(&Framebuffer::new(&[Attachment::Color0], &[&tex]), dim);
+
+        Self {
+            tex,
+            target: None,
+            packer: Packer::new(dim),
+            dim,
+        }
+    }
+
+    fn clear(fb: &Framebuffer, dim: [i32; 2]) {
+        fb.bind();
+        fb.viewport([0, 0], dim);
+        fb.clear_color([0.0, 0.0, 0.0, 0.0]);
+    }
+
+    pub fn alloc(&mut self, size: [i32; 2]) -> Option<[i32; 2]> {
+        let pos = self.packer.pack(size);
+        if pos.is_none() {
+            log::warn!("glyph atlas full, dropping {}x{} glyph", size[0], size[1]);
+        }
+
+        pos
+    }
+
+    /// `None` for distance field atlases, which have nothing to rasterise into
+    pub fn target(&self, pos: [i32; 2], size: [i32; 2]) -> Option<&Framebuffer> {
+        let (fb, _) = self.target.as_ref()?;
+        fb.bind();
+        fb.viewport(pos, size);
+        Some(fb)
+    }
+
+    pub fn upload(&self, pos: [i32; 2], field: &DistanceField) {
+        self.tex
+            .upload(pos, field.dim, Format::Rgba, &field.to_rgba());
+    }
+
+    pub fn uv(&self, [x, y]: [i32; 2], [w, h]: [i32; 2]) -> [f32; 4] {
+        let [aw, ah] = [self.dim[0] as f32, self.dim[1] as f32];
+        [
+            x as f32 / aw,
+            y as f32 / ah,
+            (x + w) as f32 / aw,
+            (y + h) as f32 / ah,
+        ]
+    }
+}
@@ -1,39 +1,224 @@
-use base_64::{
-    math::{Points, Spline},
-    mem::Vec,
-};
-use gfx_64::{
-    resource::{
-        buffer::Usage,
-        framebuffer::{Attachment, Framebuffer},
-        mesh::{Mesh, Topology},
-        pipeline::Pipeline,
-        shader::{POS2D, WHITE},
-        texture::Texture,
-    },
-    Resource,
-};
-use log;
-pub use ttf_parser::{Face, FaceParsingError, OutlineBuilder, Rect};
+mod atlas;
+mod sdf;
+
+pub use atlas::{Atlas, Packer};
+use base_64::{math::Spline, mem::Vec};
+pub use sdf::DistanceField;
+pub use ttf_parser::{Face, FaceParsingError, GlyphId, OutlineBuilder, Rect};
 
 pub type FontId = usize;
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fallback {
+    NotDef,
+    Tofu,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Raster {
+    Coverage,
+    Sdf,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GlyphKey {
+    Id(GlyphId),
+    Tofu,
+}
+
 #[derive(Debug)]
-pub struct Font {
+pub struct Font<'a> {
+    pub(crate) face: Face<'a>,
     pub(crate) glyphs: Vec<Option<Glyph>>,
+    pub(crate) tofu: Option<Glyph>,
+    pub(crate) fallback: Fallback,
+    pub(crate) raster: Raster,
+    pub(crate) atlas: Atlas,
     pub(crate) pixels_per_unit: f32,
     pub(crate) line_height: i16,
+    pub(crate) ascender: i16,
 }
 
-impl Font {
-    pub fn get(&self, idx: char) -> Option<&Glyph> {
-        self.glyphs[idx as usize].as_ref()
+impl<'a> Font<'a> {
+    pub fn new(face: Face<'a>, atlas: Atlas, raster: Raster, pixels_per_em: f32) -> Self {
+        Self {
+            glyphs: (0..face.number_of_glyphs()).map(|_| None).collect(),
+            tofu: None,
+            fallback: Fallback::NotDef,
+            raster,
+            atlas,
+            pixels_per_unit: pixels_per_em / face.units_per_em() as f32,
+            line_height: face.height(),
+            ascender: face.ascender(),
+            face,
+        }
+    }
+
+    pub fn face(&self) -> &Face<'a> {
+        &self.face
+    }
+
+    pub fn raster(&self) -> Raster {
+        self.raster
+    }
+
+    pub fn set_fallback(&mut self, fallback: Fallback) {
+        self.fallback = fallback;
+    }
+
+    pub fn key(&self, ch: char) -> GlyphKey {
+        match (self.face.glyph_index(ch), self.fallback) {
+            (Some(id), _) => GlyphKey::Id(id),
+            (None, Fallback::NotDef) => GlyphKey::Id(GlyphId(0)),
+            (None, Fallback::Tofu) => GlyphKey::Tofu,
+        }
+    }
+
+    pub fn get(&self, ch: char) -> Option<&Glyph> {
+        self.cached(self.key(ch))
+    }
+
+    pub fn cached(&self, key: GlyphKey) -> Option<&Glyph> {
+        match key {
+            GlyphKey::Id(GlyphId(id)) => self.glyphs.get(id as usize)?.as_ref(),
+            GlyphKey::Tofu => self.tofu.as_ref(),
+        }
+    }
+
+    pub(crate) fn cache(&mut self, key: GlyphKey, glyph: Glyph) {
+        match key {
+            GlyphKey::Id(GlyphId(id)) => {
+                if let Some(slot) = self.glyphs.get_mut(id as usize) {
+                    *slot = Some(glyph);
+                }
+            }
+            GlyphKey::Tofu => self.tofu = Some(glyph),
+        }
+    }
+
+    pub fn advance(&self, key: GlyphKey) -> u16 {
+        match key {
+            GlyphKey::Id(id) => self.face.glyph_hor_advance(id).unwrap_or(0),
+            GlyphKey::Tofu => (0.6 * self.face.units_per_em() as f32) as u16,
+        }
+    }
+
+    pub fn kerning(&self, left: GlyphKey, right: GlyphKey) -> i16 {
+        match (left, right) {
+            (GlyphKey::Id(left), GlyphKey::Id(right)) => self
+                .kern_kerning(left, right)
+                .or_else(|| self.gpos_kerning(left, right))
+                .unwrap_or(0),
+            _ => 0,
+        }
+    }
+
+    fn kern_kerning(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+        self.face
+            .tables()
+            .kern?
+            .subtables
+            .into_iter()
+            .filter(|table| table.horizontal && !table.has_cross_stream)
+            .find_map(|table| table.glyphs_kerning(left, right))
+    }
+
+    #[cfg(feature = "gpos")]
+    fn gpos_kerning(&self, left: GlyphId, right: GlyphId) -> Option<i16> {
+        use ttf_parser::{
+            gpos::{PairAdjustment, PositioningSubtable},
+            Tag,
+        };
+
+        let gpos = self.face.tables().gpos?;
+        gpos.features
+            .into_iter()
+            .filter(|feature| feature.tag == Tag::from_bytes(b"kern"))
+            .flat_map(|feature| feature.lookup_indices)
+            .filter_map(|idx| gpos.lookups.get(idx))
+            .flat_map(|lookup| lookup.subtables.into_iter::<PositioningSubtable>())
+            .find_map(|subtable| match subtable {
+                PositioningSubtable::Pair(pair) => {
+                    let idx = pair.coverage().get(left)?;
+                    let (first, _) = match pair {
+                        PairAdjustment::Format1 { sets, .. } => sets.get(idx)?.get(right)?,
+                        PairAdjustment::Format2 {
+                            classes, matrix, ..
+                        } => matrix.get((classes.0.get(left), classes.1.get(right)))?,
+                    };
+                    Some(first.x_advance)
+                }
+                _ => None,
+            })
+    }
+
+    #[cfg(not(feature = "gpos"))]
+    fn gpos_kerning(&self, _: GlyphId, _: GlyphId) -> Option<i16> {
+        None
+    }
+
+    pub(crate) fn outline(&self, key: GlyphKey) -> (SplineBuilder, Rect, Glyph) {
+        let mut outline = SplineBuilder::new();
+        match key {
+            GlyphKey::Id(id) => {
+                let bbox = self.face.outline_glyph(id, &mut outline).unwrap_or(Rect {
+                    x_min: 0,
+                    y_min: 0,
+                    x_max: 0,
+                    y_max: 0,
+                });
+
+                let glyph = Glyph {
+                    uv: None,
+                    size: [
+                        (bbox.x_max - bbox.x_min) as i32,
+                        (bbox.y_max - bbox.y_min) as i32,
+                    ],
+                    bearing: [bbox.x_min as i32, bbox.y_min as i32],
+                    h_advance: self.face.glyph_hor_advance(id).unwrap_or(0),
+                };
+                (outline, bbox, glyph)
+            }
+
+            GlyphKey::Tofu => {
+                let em = self.face.units_per_em() as f32;
+                let height = self.face.capital_height().map_or(0.7 * em, |h| h as f32);
+                let stroke = em / 16.0;
+                let [left, right] = [em / 10.0, em / 2.0];
+
+                // Outer and inner boxes; the stencil fill is even-odd so the inner one cuts a hole
+                for [x0, y0, x1, y1] in [
+                    [left, 0.0, right, height],
+                    [left + stroke, stroke, right - stroke, height - stroke],
+                ] {
+                    outline.move_to(x0, y0);
+                    outline.line_to(x1, y0);
+                    outline.line_to(x1, y1);
+                    outline.line_to(x0, y1);
+                    outline.line_to(x0, y0);
+                }
+
+                let bbox = Rect {
+                    x_min: left as i16,
+                    y_min: 0,
+                    x_max: right as i16,
+                    y_max: height as i16,
+                };
+                let glyph = Glyph {
+                    uv: None,
+                    size: [(right - left) as i32, height as i32],
+                    bearing: [left as i32, 0],
+                    h_advance: self.advance(key),
+                };
+                (outline, bbox, glyph)
+            }
+        }
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Copy, Debug)]
 pub struct Glyph {
-    pub tex: Option<Texture>,
+    pub uv: Option<[f32; 4]>,
     pub size: [i32; 2],
     pub bearing: [i32; 2],
     pub h_advance: u16,
@@ -0,0 +1,98 @@
+use super::SplineBuilder;
+use base_64::{
+    math::{sqrt, Curve},
+    mem::Vec,
+};
+
+const CURVE_STEPS: usize = 8;
+
+#[derive(Debug)]
+pub struct DistanceField {
+    pub dim: [i32; 2],
+    /// One byte per texel, bottom row first; 128 is the outline, higher is inside
+    pub pixels: Vec<u8>,
+}
+
+impl DistanceField {
+    pub fn to_rgba(&self) -> Vec<u8> {
+        self.pixels
+            .iter()
+            .flat_map(|&d| [0xFF, 0xFF, 0xFF, d])
+            .collect()
+    }
+}
+
+impl SplineBuilder {
+    /// Flattens every spline into closed polylines
+    fn segments(&self) -> Vec<[[f32; 2]; 2]> {
+        let mut segments = Vec::new();
+        for spline in self.splines.iter() {
+            for bezier in spline.iter() {
+                let points = match bezier.len() {
+                    0 | 1 => continue,
+                    2 => bezier.clone(),
+                    _ => bezier.plot(0.0, 1.0, CURVE_STEPS),
+                };
+
+                for pair in points.windows(2) {
+                    segments.push([pair[0], pair[1]]);
+                }
+            }
+        }
+
+        segments
+    }
+
+    /// Samples the signed distance to the outline at each texel centre of `dim` laid over
+    /// `[left, bottom, right, top]`; distances are clamped to `spread`, all in outline units
+    pub fn distance_field(
+        &self,
+        [left, bottom, right, top]: [f32; 4],
+        dim: [i32; 2],
+        spread: f32,
+    ) -> DistanceField {
+        let segments = self.segments();
+        let step = [
+            (right - left) / dim[0] as f32,
+            (top - bottom) / dim[1] as f32,
+        ];
+
+        let mut pixels = Vec::with_capacity((dim[0] * dim[1]) as usize);
+        for row in 0..dim[1] {
+            let y = bottom + (row as f32 + 0.5) * step[1];
+            for col in 0..dim[0] {
+                let x = left + (col as f32 + 0.5) * step[0];
+
+                let mut closest = f32::MAX;
+                let mut inside = false;
+                for &[[x0, y0], [x1, y1]] in segments.iter() {
+                    closest = closest.min(distance_squared([x, y], [x0, y0], [x1, y1]));
+
+                    // Even-odd, matching the stencil fill
+                    if (y0 > y) != (y1 > y) && x < x0 + (y - y0) * (x1 - x0) / (y1 - y0) {
+                        inside = !inside;
+                    }
+                }
+
+                let distance = if inside { 1.0 } else { -1.0 } * sqrt(closest);
+                let value = 0.5 + 0.5 * (distance / spread).clamp(-1.0, 1.0);
+                pixels.push((value * 255.0 + 0.5) as u8);
+            }
+        }
+
+        DistanceField { dim, pixels }
+    }
+}
+
+fn distance_squared([px, py]: [f32; 2], [x0, y0]: [f32; 2], [x1, y1]: [f32; 2]) -> f32 {
+    let [dx, dy] = [x1 - x0, y1 - y0];
+    let len = dx * dx + dy * dy;
+    let t = if len > 0.0 {
+        (((px - x0) * dx + (py - y0) * dy) / len).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+
+    let [ex, ey] = [px - (x0 + t * dx), py - (y0 + t * dy)];
+    ex * ex + ey * ey
+}
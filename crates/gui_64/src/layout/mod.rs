@@ -0,0 +1,240 @@
+use crate::font::{Font, FontId, GlyphKey};
+use base_64::{math::floor, mem::Vec};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct TextStyle {
+    pub font: FontId,
+    pub em: f32,
+    pub color: [f32; 4],
+    pub align: Align,
+    pub max_width: Option<f32>,
+    pub line_spacing: f32,
+    pub tab_width: f32,
+    /// SDF fonts only; widths and offsets are in ems
+    pub outline: Option<(f32, [f32; 4])>,
+    pub glow: Option<(f32, [f32; 4])>,
+    pub shadow: Option<([f32; 2], [f32; 4])>,
+}
+
+impl TextStyle {
+    pub fn new(font: FontId, em: f32) -> Self {
+        Self {
+            font,
+            em,
+            color: [1.0; 4],
+            align: Align::Left,
+            max_width: None,
+            line_spacing: 1.0,
+            tab_width: 4.0,
+            outline: None,
+            glow: None,
+            shadow: None,
+        }
+    }
+
+    pub fn color(self, color: [f32; 4]) -> Self {
+        Self { color, ..self }
+    }
+
+    pub fn align(self, align: Align) -> Self {
+        Self { align, ..self }
+    }
+
+    pub fn max_width(self, max_width: f32) -> Self {
+        Self {
+            max_width: Some(max_width),
+            ..self
+        }
+    }
+
+    pub fn line_spacing(self, line_spacing: f32) -> Self {
+        Self {
+            line_spacing,
+            ..self
+        }
+    }
+
+    pub fn tab_width(self, tab_width: f32) -> Self {
+        Self { tab_width, ..self }
+    }
+
+    pub fn outline(self, width: f32, color: [f32; 4]) -> Self {
+        Self {
+            outline: Some((width, color)),
+            ..self
+        }
+    }
+
+    pub fn glow(self, width: f32, color: [f32; 4]) -> Self {
+        Self {
+            glow: Some((width, color)),
+            ..self
+        }
+    }
+
+    pub fn shadow(self, offset: [f32; 2], color: [f32; 4]) -> Self {
+        Self {
+            shadow: Some((offset, color)),
+            ..self
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct PositionedGlyph {
+    pub ch: char,
+    pub key: GlyphKey,
+    pub pos: [f32; 2],
+    pub line: usize,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Line {
+    start: usize,
+    end: usize,
+    width: f32,
+}
+
+#[derive(Debug)]
+pub struct Layout {
+    pub glyphs: Vec<PositionedGlyph>,
+    pub bounds: [f32; 4],
+    pub scale: f32,
+    pub lines: usize,
+}
+
+pub fn layout(font: &Font<'_>, style: &TextStyle, text: &str, [x, y]: [f32; 2]) -> Layout {
+    let scale = style.em * font.pixels_per_unit;
+    let line_height = font.line_height as f32 * scale * style.line_spacing;
+    // Fonts without a space glyph still get tab stops
+    let space = match font.advance(font.key(' ')) {
+        0 => font.face.units_per_em() / 4,
+        advance => advance,
+    };
+    let tab = space as f32 * scale * style.tab_width;
+
+    let extent = |glyphs: &[PositionedGlyph]| {
+        glyphs
+            .iter()
+            .filter(|glyph| !glyph.ch.is_whitespace())
+            .map(|glyph| glyph.pos[0] + font.advance(glyph.key) as f32 * scale)
+            .fold(0.0, f32::max)
+    };
+
+    let mut glyphs: Vec<PositionedGlyph> = Vec::with_capacity(text.len());
+    let mut lines: Vec<Line> = Vec::with_capacity(1);
+
+    for paragraph in text.split('\n') {
+        let mut line = Line {
+            start: glyphs.len(),
+            end: glyphs.len(),
+            width: 0.0,
+        };
+        let mut pen = 0.0;
+        let mut prev = None;
+        // Index of the first glyph after the latest space
+        let mut wrap_at: Option<usize> = None;
+
+        for ch in paragraph.chars() {
+            let key = font.key(ch);
+            if ch == '\t' {
+                if tab > 0.0 {
+                    pen = (floor(pen / tab) + 1.0) * tab;
+                }
+                wrap_at = Some(glyphs.len());
+                prev = None;
+                continue;
+            }
+
+            let mut kern = prev.map_or(0.0, |prev| font.kerning(prev, key) as f32 * scale);
+            let advance = font.advance(key) as f32 * scale;
+
+            let overflows = style
+                .max_width
+                .is_some_and(|max| !ch.is_whitespace() && pen + kern + advance > max);
+            if overflows && glyphs.len() > line.start {
+                // Break at the last space, or mid-word if the word alone is too wide
+                let start = match wrap_at {
+                    Some(start) if start > line.start => start,
+                    _ => glyphs.len(),
+                };
+                // The new line starts flush, unkerned against the glyph before the break
+                let shift = glyphs.get(start).map_or(pen, |glyph| glyph.pos[0]);
+                if start == glyphs.len() {
+                    kern = 0.0;
+                }
+
+                line.end = start;
+                line.width = extent(&glyphs[line.start..start]);
+                lines.push(line);
+                line = Line {
+                    start,
+                    end: start,
+                    width: 0.0,
+                };
+
+                for glyph in glyphs[start..].iter_mut() {
+                    glyph.pos[0] -= shift;
+                    glyph.line = lines.len();
+                }
+                pen -= shift;
+                line.width = extent(&glyphs[start..]);
+                wrap_at = None;
+            }
+
+            pen += kern;
+            glyphs.push(PositionedGlyph {
+                ch,
+                key,
+                pos: [pen, 0.0],
+                line: lines.len(),
+            });
+            pen += advance;
+
+            if ch.is_whitespace() {
+                wrap_at = Some(glyphs.len());
+            } else {
+                line.width = pen;
+            }
+            prev = Some(key);
+        }
+
+        line.end = glyphs.len();
+        lines.push(line);
+    }
+
+    let block_width = style
+        .max_width
+        .unwrap_or_else(|| lines.iter().map(|line| line.width).fold(0.0, f32::max));
+    let baseline = y - font.ascender as f32 * scale;
+
+    let mut bounds = [f32::MAX, y - line_height * lines.len() as f32, f32::MIN, y];
+    for (n, line) in lines.iter().enumerate() {
+        let offset = match style.align {
+            Align::Left => 0.0,
+            Align::Center => (block_width - line.width) / 2.0,
+            Align::Right => block_width - line.width,
+        };
+
+        for glyph in glyphs[line.start..line.end].iter_mut() {
+            glyph.pos = [x + offset + glyph.pos[0], baseline - n as f32 * line_height];
+        }
+
+        bounds[0] = bounds[0].min(x + offset);
+        bounds[2] = bounds[2].max(x + offset + line.width);
+    }
+
+    Layout {
+        glyphs,
+        bounds,
+        scale,
+        lines: lines.len(),
+    }
+}
@@ -1,47 +1,86 @@
 #![no_std]
+pub mod batch;
+pub mod error;
 pub mod font;
+pub mod layout;
 
 use base_64::{
-    math::{ortho, Points},
+    math::{ceil, ortho, Points},
     mem::Vec,
 };
-use font::{Face, Font, FontId, Glyph, Rect, SplineBuilder};
+use batch::TextBatch;
+pub use error::Error;
+use font::{Atlas, Face, Font, FontId, Glyph, Raster, Rect, SplineBuilder};
 use gfx_64::{
     resource::{
         framebuffer::{Attachment, Framebuffer},
-        mesh::{Mesh, Topology, Usage},
-        shader::{POS2D, POS2D_TEX2D, TEX2D, WHITE},
-        texture::{Format, Target, Texture},
+        mesh::{Mesh, Primitive, Usage},
+        shader::{POS2D_TEX2D, TEX2D},
+        texture::{Filter, Format, Target, Texture},
     },
-    Draw, Pipeline, RenderTarget, Resource, Stencil, Uniform,
+    shader_source, Blend, Draw, Image, Pipeline, RenderTarget, Resource, Source, Stencil, Uniform,
 };
-use ttf_parser::FaceParsingError;
+use layout::{layout, Layout, TextStyle};
 
 pub const HACK_TTF: &[u8] = include_bytes!("../assets/Hack/build/ttf/Hack-Regular.ttf");
-const TEXT_VERT: &str = concat!(include_str!("../assets/shaders/text.vert"), "\0");
-const TEXT_FRAG: &str = concat!(include_str!("../assets/shaders/text.frag"), "\0");
+const TEXT_VERT: Source = shader_source!(Vertex, "assets/shaders/text.vert");
+const TEXT_FRAG: Source = shader_source!(Fragment, "assets/shaders/text.frag");
+const SDF_FRAG: Source = shader_source!(Fragment, "assets/shaders/sdf.frag");
+const GLYPH_VERT: Source = shader_source!(Vertex, "assets/shaders/glyph.vert");
+const GLYPH_FRAG: Source = shader_source!(Fragment, "assets/shaders/glyph.frag");
 const PIXELS_PER_EM: f32 = 16.0;
+const ATLAS_PIXELS_PER_EM: f32 = 64.0;
+const ATLAS_DIM: [i32; 2] = [1024, 1024];
+const SDF_PIXELS_PER_EM: f32 = 48.0;
+// Distance in atlas pixels at which the field saturates, which also bounds outline and glow width
+const SDF_SPREAD: f32 = 6.0;
 
-pub struct GuiSystem {
-    fonts: Vec<Font>,
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Quality {
+    /// One stencil pass per glyph, hard edges
+    #[default]
+    Aliased,
+    /// Coverage averaged over this many subpixel-jittered stencil passes
+    Jittered(u8),
+}
+
+impl Quality {
+    fn filter(self) -> Filter {
+        match self {
+            // Rasterised with a single sample, just like aliased
+            Quality::Aliased | Quality::Jittered(0..=1) => Filter::Nearest,
+            Quality::Jittered(_) => Filter::Linear,
+        }
+    }
+}
+
+/// Borrows the font files it loads for `'a`
+pub struct GuiSystem<'a> {
+    fonts: Vec<Font<'a>>,
     txt_pipe: Pipeline,
+    sdf_pipe: Pipeline,
     gui_pipe: Pipeline,
     stencil_pipe: Pipeline,
     view: Texture,
     fb: Framebuffer,
     quad: Mesh,
+    batch: TextBatch,
+    quality: Quality,
     dim: [f32; 2],
 }
 
-impl GuiSystem {
-    pub fn new(dim: [i32; 2]) -> Self {
+impl<'a> GuiSystem<'a> {
+    pub fn new(dim: [i32; 2]) -> Result<Self, Error> {
         log::info!("initializing GuiSystem");
-        let txt_pipe = Pipeline::new(TEXT_VERT, TEXT_FRAG);
-        let gui_pipe = Pipeline::new(POS2D_TEX2D, TEX2D);
-        let stencil_pipe = Pipeline::new(POS2D, WHITE);
+        let txt_pipe = Pipeline::new(TEXT_VERT, TEXT_FRAG)?;
+        let sdf_pipe = Pipeline::new(TEXT_VERT, SDF_FRAG)?;
+        let gui_pipe = Pipeline::new(POS2D_TEX2D, TEX2D)?;
+        let stencil_pipe = Pipeline::new(GLYPH_VERT, GLYPH_FRAG)?;
 
         let view = Texture::new(Target::Tex2d, dim, Format::Rgba);
         let fb = Framebuffer::new(&[Attachment::Color0], &[&view]);
+        fb.bind();
+        fb.clear_color([0.0, 0.0, 0.0, 0.0]);
 
         let quad = Mesh::new(
             &[
@@ -51,165 +90,360 @@ impl GuiSystem {
                 ([1.0, -1.0], [1.0, 0.0]),
             ],
             Usage::StaticDraw,
-            Topology::TriStrip,
+            Primitive::TriStrip,
         );
 
         let dim = [dim[0] as f32, dim[1] as f32];
-        Self {
+        Ok(Self {
             fonts: Vec::new(),
             txt_pipe,
+            sdf_pipe,
             gui_pipe,
             stencil_pipe,
             view,
             fb,
             quad,
+            batch: TextBatch::new(),
+            quality: Quality::default(),
             dim,
-        }
+        })
+    }
+
+    /// Reallocates the render target, which drops everything drawn so far
+    pub fn resize(&mut self, dim: [i32; 2]) {
+        log::debug!("resizing gui to {}x{}", dim[0], dim[1]);
+        self.view = Texture::new(Target::Tex2d, dim, Format::Rgba);
+        self.fb = Framebuffer::new(&[Attachment::Color0], &[&self.view]);
+        self.dim = [dim[0] as f32, dim[1] as f32];
+        self.clear();
     }
 
-    pub fn load_font(&mut self, file: &[u8]) -> Result<FontId, FaceParsingError> {
-        log::debug!("loading font");
+    pub fn dim(&self) -> [f32; 2] {
+        self.dim
+    }
+
+    pub fn load_font(&mut self, file: &'a [u8]) -> Result<FontId, Error> {
+        self.load(file, Raster::Coverage)
+    }
+
+    /// Loads a font whose glyphs are stored as distance fields, which stay sharp at any `em`
+    pub fn load_sdf_font(&mut self, file: &'a [u8]) -> Result<FontId, Error> {
+        self.load(file, Raster::Sdf)
+    }
+
+    fn load(&mut self, file: &'a [u8], raster: Raster) -> Result<FontId, Error> {
+        log::debug!("loading {:?} font", raster);
         let face = Face::from_slice(file, 0)?;
+        let atlas = match raster {
+            Raster::Coverage => Atlas::new(ATLAS_DIM, self.quality.filter()),
+            Raster::Sdf => Atlas::sdf(ATLAS_DIM),
+        };
+        self.fonts
+            .push(Font::new(face, atlas, raster, PIXELS_PER_EM));
+
+        let font_id = self.fonts.len() - 1;
+        for ch in ' '..='~' {
+            self.glyph(font_id, ch);
+        }
+
+        Ok(font_id)
+    }
 
-        let mut glyphs = Vec::with_capacity(128);
-        for ch in 0..128u8 {
-            glyphs.push(self.draw_glyph(&face, ch as char));
+    pub fn quality(&self) -> Quality {
+        self.quality
+    }
+
+    /// Only affects glyphs rasterised after the switch; already cached glyphs keep their coverage
+    pub fn set_quality(&mut self, quality: Quality) {
+        log::debug!("glyph quality: {:?}", quality);
+        self.quality = quality;
+        for font in self.fonts.iter() {
+            if font.raster == Raster::Coverage {
+                font.atlas.tex.filter(quality.filter());
+            }
+        }
+    }
+
+    pub fn font(&self, font_id: FontId) -> &Font<'a> {
+        &self.fonts[font_id]
+    }
+
+    pub fn font_mut(&mut self, font_id: FontId) -> &mut Font<'a> {
+        &mut self.fonts[font_id]
+    }
+
+    pub fn glyph(&mut self, font_id: FontId, ch: char) -> Glyph {
+        let Self {
+            fonts,
+            stencil_pipe,
+            quad,
+            quality,
+            ..
+        } = self;
+        let font = &mut fonts[font_id];
+
+        let key = font.key(ch);
+        if let Some(glyph) = font.cached(key) {
+            return *glyph;
+        }
+
+        log::debug!("rendering glyph '{}' ({:?})", ch, key);
+        let (outline, bbox, mut glyph) = font.outline(key);
+        let units_per_em = font.face.units_per_em() as f32;
+        match font.raster {
+            Raster::Coverage => {
+                glyph.uv = Self::rasterise(
+                    stencil_pipe,
+                    quad,
+                    &mut font.atlas,
+                    &outline,
+                    bbox,
+                    ATLAS_PIXELS_PER_EM / units_per_em,
+                    *quality,
+                );
+            }
+            Raster::Sdf => {
+                let raster_scale = SDF_PIXELS_PER_EM / units_per_em;
+                // Pad the quad so the field has room to fall off around the outline
+                let pad = ceil(SDF_SPREAD / raster_scale) as i32;
+                glyph.bearing = [glyph.bearing[0] - pad, glyph.bearing[1] - pad];
+                glyph.size = [glyph.size[0] + 2 * pad, glyph.size[1] + 2 * pad];
+                glyph.uv = Self::distance_field(&mut font.atlas, &outline, bbox, pad, raster_scale);
+            }
         }
 
-        self.fonts.push(Font {
-            glyphs,
-            pixels_per_unit: PIXELS_PER_EM / face.units_per_em() as f32,
-            line_height: face.height(),
-        });
-        Ok(self.fonts.len() - 1)
+        font.cache(key, glyph);
+        glyph
     }
 
-    pub fn draw_text(&mut self, font_id: FontId, text: &str, [x, y]: [f32; 2], em: f32) {
-        let font = &self.fonts[font_id];
-        let scale = em * font.pixels_per_unit;
+    pub fn clear(&self) {
+        self.fb.bind();
+        self.fb.clear_color([0.0, 0.0, 0.0, 0.0]);
+    }
+
+    pub fn draw_text(
+        &mut self,
+        font_id: FontId,
+        text: &str,
+        pos: [f32; 2],
+        em: f32,
+        color: [f32; 4],
+    ) {
+        self.draw_text_styled(&TextStyle::new(font_id, em).color(color), text, pos);
+    }
+
+    pub fn measure_text(&self, style: &TextStyle, text: &str) -> [f32; 4] {
+        layout(&self.fonts[style.font], style, text, [0.0, 0.0]).bounds
+    }
+
+    pub fn draw_text_styled(&mut self, style: &TextStyle, text: &str, pos: [f32; 2]) {
+        let font_id = style.font;
+        let Layout { glyphs, scale, .. } = layout(&self.fonts[font_id], style, text, pos);
+
+        for placed in glyphs.iter() {
+            let glyph = self.glyph(font_id, placed.ch);
+            if let Some([u0, v0, u1, v1]) = glyph.uv {
+                let [x, y] = placed.pos;
+                let left = x + glyph.bearing[0] as f32 * scale;
+                let bottom = y + glyph.bearing[1] as f32 * scale;
+                let right = left + glyph.size[0] as f32 * scale;
+                let top = bottom + glyph.size[1] as f32 * scale;
+
+                self.batch
+                    .quad([left, bottom, right, top], [u0, v0, u1, v1]);
+            }
+        }
+
+        // Glyph rasterisation rebinds targets, so set up the text pass once everything is cached
         self.fb.bind();
         self.fb
             .viewport([0, 0], [self.dim[0] as i32, self.dim[1] as i32]);
-        self.txt_pipe.bind();
+        match self.fonts[font_id].raster {
+            Raster::Coverage => self.txt_pipe.bind(),
+            Raster::Sdf => {
+                self.sdf_pipe.bind();
+                Self::bind_effects(style);
+            }
+        }
+        self.fonts[font_id].atlas.tex.bind();
 
         ortho([0.0, 0.0], self.dim).bind(0);
-        let mut y = y;
-        for line in text.split(|byte| byte == '\n') {
-            y -= font.line_height as f32 * scale;
-
-            let mut x = x;
-            for ch in line.chars() {
-                let glyph = font.get(ch).expect("character not found");
-                if let Some(tex) = &glyph.tex {
-                    let [w, h] = [glyph.size[0] as f32 * scale, glyph.size[1] as f32 * scale];
-                    let [dx, dy] = [
-                        glyph.bearing[0] as f32 * scale,
-                        glyph.bearing[1] as f32 * scale,
-                    ];
+        style.color.bind(1);
+        Blend::Premultiplied.apply();
+        self.batch.flush();
+        // Leave blending as draws outside the gui expect it
+        Blend::Off.apply();
+    }
 
-                    let left = x + dx;
-                    let right = left + w;
-                    let bottom = y + dy;
-                    let top = bottom + h;
-
-                    tex.bind();
-                    Mesh::new(
-                        &[
-                            ([left, top], [0.0, 1.0]),
-                            ([right, top], [1.0, 1.0]),
-                            ([left, bottom], [0.0, 0.0]),
-                            ([right, bottom], [1.0, 0.0]),
-                        ],
-                        Usage::StaticDraw,
-                        Topology::TriStrip,
-                    )
-                    .draw();
-                }
+    pub fn snapshot(&self) -> Image {
+        self.fb
+            .read_pixels([0, 0], [self.dim[0] as i32, self.dim[1] as i32])
+    }
 
-                x += glyph.h_advance as f32 * scale;
-            }
+    fn bind_effects(style: &TextStyle) {
+        // Field values run 0..1 across twice the spread, in atlas pixels
+        let distance = |em: f32| em * SDF_PIXELS_PER_EM * 0.5 / SDF_SPREAD;
+        let none = (0.0, [0.0; 4]);
+
+        let (width, color) = style.outline.unwrap_or(none);
+        color.bind(2);
+        distance(width).bind(3);
+
+        let (width, color) = style.glow.unwrap_or(none);
+        color.bind(4);
+        distance(width).bind(5);
+
+        let ([x, y], color) = style.shadow.unwrap_or(([0.0; 2], [0.0; 4]));
+        color.bind(6);
+        [
+            x * SDF_PIXELS_PER_EM / ATLAS_DIM[0] as f32,
+            y * SDF_PIXELS_PER_EM / ATLAS_DIM[1] as f32,
+        ]
+        .bind(7);
+    }
+
+    fn distance_field(
+        atlas: &mut Atlas,
+        outline: &SplineBuilder,
+        Rect {
+            x_min,
+            y_min,
+            x_max,
+            y_max,
+        }: Rect,
+        pad: i32,
+        raster_scale: f32,
+    ) -> Option<[f32; 4]> {
+        if outline.splines.is_empty() {
+            return None;
         }
+
+        let bounds = [
+            (x_min as i32 - pad) as f32,
+            (y_min as i32 - pad) as f32,
+            (x_max as i32 + pad) as f32,
+            (y_max as i32 + pad) as f32,
+        ];
+        let raster_size = [
+            ceil((bounds[2] - bounds[0]) * raster_scale) as i32,
+            ceil((bounds[3] - bounds[1]) * raster_scale) as i32,
+        ];
+
+        let pos = atlas.alloc(raster_size)?;
+        log::trace!(
+            "generating {}x{} distance field",
+            raster_size[0],
+            raster_size[1]
+        );
+        let field = outline.distance_field(bounds, raster_size, SDF_SPREAD / raster_scale);
+        atlas.upload(pos, &field);
+
+        Some(atlas.uv(pos, raster_size))
     }
 
-    fn draw_glyph(&mut self, face: &Face, ch: char) -> Option<Glyph> {
-        let mut outline = SplineBuilder::new();
-        face.glyph_index(ch).map_or(None, |idx| {
-            face.outline_glyph(idx, &mut outline).map_or(
-                None,
-                |Rect {
-                     x_max,
-                     x_min,
-                     y_max,
-                     y_min,
-                 }| {
-                    log::debug!("rendering glyph '{}' ({}u8)", ch, ch as u8);
-                    let bearing = [
-                        face.glyph_hor_side_bearing(idx).unwrap_or(0) as i32,
-                        face.glyph_ver_side_bearing(idx).unwrap_or(0) as i32,
+    fn rasterise(
+        stencil_pipe: &Pipeline,
+        quad: &Mesh,
+        atlas: &mut Atlas,
+        outline: &SplineBuilder,
+        Rect {
+            x_min,
+            y_min,
+            x_max,
+            y_max,
+        }: Rect,
+        raster_scale: f32,
+        quality: Quality,
+    ) -> Option<[f32; 4]> {
+        let size = [(x_max - x_min) as i32, (y_max - y_min) as i32];
+        // Nothing to cover, and the vertices below would divide by zero
+        if outline.splines.is_empty() || size[0] == 0 || size[1] == 0 {
+            return None;
+        }
+
+        // Build meshes
+        let verts = outline.splines.iter().fold(
+            Vec::with_capacity(outline.splines.len() * 100 + 1),
+            |mut points, spline| {
+                points.push([0.0, 0.0]);
+                spline.points().iter().fold(points, |mut points, point| {
+                    let point = [
+                        (2.0 * (point[0] - x_min as f32) / size[0] as f32) - 1.0,
+                        (2.0 * (point[1] - y_min as f32) / size[1] as f32) - 1.0,
                     ];
+                    points.push(point);
+
+                    points
+                })
+            },
+        );
 
-                    let size = [(x_max - x_min) as i32, (y_max - y_min) as i32];
-
-                    // Build meshes
-                    let verts = outline.splines.iter().fold(
-                        Vec::with_capacity(outline.splines.len() * 100 + 1),
-                        |mut points, spline| {
-                            points.push([0.0, 0.0]);
-                            spline.points().iter().fold(points, |mut points, point| {
-                                let point = [
-                                    (2.0 * (point[0] - x_min as f32) / size[0] as f32) - 1.0,
-                                    (2.0 * (point[1] - y_min as f32) / size[1] as f32) - 1.0,
-                                ];
-                                points.push(point);
-
-                                points
-                            })
-                        },
-                    );
-
-                    log::trace!("calculated glyph vertices: {:?}", verts);
-                    let glyph = Mesh::new(&verts, Usage::StaticDraw, Topology::TriFan);
-
-                    log::trace!("initializing full screen quad");
-                    let quad = Mesh::new(
-                        &[[-1.0, 1.0], [1.0, 1.0], [-1.0, -1.0], [1.0, -1.0]],
-                        Usage::StaticDraw,
-                        Topology::TriStrip,
-                    );
-
-                    log::trace!("preparing glyph render target");
-                    let tex = Texture::new(Target::Tex2d, size, Format::Rgba);
-                    let stencil = Texture::new(Target::Tex2d, size, Format::Stencil);
-                    let fb = Framebuffer::new(
-                        &[Attachment::Color0, Attachment::Stencil],
-                        &[&tex, &stencil],
-                    );
-
-                    fb.bind();
-                    fb.viewport([0, 0], size);
-                    fb.clear_color([0.0, 0.0, 0.0, 0.0]);
-
-                    self.stencil_pipe.bind();
+        log::trace!("calculated glyph vertices: {:?}", verts);
+        let glyph = Mesh::new(&verts, Usage::StaticDraw, Primitive::TriFan);
+
+        log::trace!("packing glyph into atlas");
+        let raster_size = [
+            ceil(size[0] as f32 * raster_scale) as i32,
+            ceil(size[1] as f32 * raster_scale) as i32,
+        ];
+        atlas.alloc(raster_size).and_then(|pos| {
+            atlas.target(pos, raster_size)?;
+            stencil_pipe.bind();
+
+            match quality {
+                Quality::Aliased | Quality::Jittered(0..=1) => {
+                    Blend::Off.apply();
+                    [0.0, 0.0].bind(0);
+                    1.0.bind(1);
                     glyph.stencil();
                     quad.draw();
+                }
+                Quality::Jittered(samples) => {
+                    // Atlas regions are never reused and start out cleared, so coverage can simply add up
+                    Blend::Additive.apply();
+                    // Coverage is 8 bit, so hand out 255 in whole steps or fully covered texels
+                    // end short of it whenever `samples` doesn't divide 255
+                    let share = |n: u32| 255 * n / samples as u32;
+                    for n in 1..=samples as u32 {
+                        ((share(n) - share(n - 1)) as f32 / 255.0).bind(1);
+                        // Subpixel offsets in [-0.5, 0.5), converted to clip space
+                        let offset = [
+                            (halton(n, 2) - 0.5) * 2.0 / raster_size[0] as f32,
+                            (halton(n, 3) - 0.5) * 2.0 / raster_size[1] as f32,
+                        ];
+                        offset.bind(0);
+                        glyph.stencil();
+                        [0.0, 0.0].bind(0);
+                        quad.draw();
+                    }
+                    Blend::Off.apply();
+                }
+            }
 
-                    Some(Glyph {
-                        tex: Some(tex),
-                        size,
-                        bearing,
-                        h_advance: face.glyph_hor_advance(idx).unwrap_or(0),
-                    })
-                },
-            )
+            Some(atlas.uv(pos, raster_size))
         })
     }
 }
 
-impl Draw for GuiSystem {
+fn halton(mut n: u32, base: u32) -> f32 {
+    let mut f = 1.0;
+    let mut r = 0.0;
+    while n > 0 {
+        f /= base as f32;
+        r += f * (n % base) as f32;
+        n /= base;
+    }
+
+    r
+}
+
+impl Draw for GuiSystem<'_> {
     fn draw(&self) {
         log::debug!("drawing gui");
+        Blend::Premultiplied.apply();
         self.gui_pipe.bind();
         self.view.bind();
         self.quad.draw();
+        Blend::Off.apply();
     }
 }
@@ -0,0 +1,297 @@
+mod ring;
+#[cfg(test)]
+mod tests;
+
+use crate::Error;
+use base_64::mem::Vec;
+use core::{ffi::c_void, mem::size_of, slice};
+pub use ring::Consumer;
+use ring::Ring;
+use sdl2_sys::{
+    SDL_AudioDeviceID, SDL_AudioSpec, SDL_AudioStatus, SDL_CloseAudioDevice,
+    SDL_GetAudioDeviceStatus, SDL_InitSubSystem, SDL_LockAudioDevice, SDL_OpenAudioDevice,
+    SDL_PauseAudioDevice, SDL_QuitSubSystem, SDL_UnlockAudioDevice, SDL_setenv, AUDIO_F32SYS,
+    AUDIO_S16SYS, AUDIO_S32SYS, AUDIO_U8, SDL_AUDIO_ALLOW_FREQUENCY_CHANGE,
+    SDL_AUDIO_ALLOW_SAMPLES_CHANGE, SDL_INIT_AUDIO,
+};
+
+pub trait Sample: Copy + Send + 'static {
+    const FORMAT: u16;
+    const SILENCE: Self;
+}
+
+impl Sample for u8 {
+    const FORMAT: u16 = AUDIO_U8 as u16;
+    const SILENCE: Self = 0x80;
+}
+
+impl Sample for i16 {
+    const FORMAT: u16 = AUDIO_S16SYS as u16;
+    const SILENCE: Self = 0;
+}
+
+impl Sample for i32 {
+    const FORMAT: u16 = AUDIO_S32SYS as u16;
+    const SILENCE: Self = 0;
+}
+
+impl Sample for f32 {
+    const FORMAT: u16 = AUDIO_F32SYS as u16;
+    const SILENCE: Self = 0.0;
+}
+
+/// Runs on SDL's audio thread; `out` is interleaved by channel and must be filled completely
+pub trait Callback: Send + 'static {
+    type Sample: Sample;
+
+    fn fill(&mut self, out: &mut [Self::Sample]);
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Spec {
+    pub freq: i32,
+    pub channels: u8,
+    /// Frames per callback, what SDL actually picked is in `Device::spec`
+    pub samples: u16,
+}
+
+impl Default for Spec {
+    fn default() -> Self {
+        Self {
+            freq: 48000,
+            channels: 2,
+            samples: 1024,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Driver<'a> {
+    Default,
+    /// Discards everything, but still runs the callback in real time
+    Dummy,
+    /// Writes raw samples to the given nul-terminated path
+    Disk(&'a [u8]),
+}
+
+/// Keeps the audio subsystem up; devices must be dropped before it
+pub struct Audio;
+
+impl Audio {
+    pub fn new(driver: Driver) -> Result<Self, Error> {
+        unsafe {
+            // SDL only reads the driver choice from the environment when the subsystem starts
+            let name: &[u8] = match driver {
+                Driver::Default => b"\0",
+                Driver::Dummy => b"dummy\0",
+                Driver::Disk(path) => {
+                    SDL_setenv(c"SDL_DISKAUDIOFILE".as_ptr(), path.as_ptr() as *const i8, 1);
+                    b"disk\0"
+                }
+            };
+            if driver != Driver::Default {
+                SDL_setenv(c"SDL_AUDIODRIVER".as_ptr(), name.as_ptr() as *const i8, 1);
+            }
+
+            if SDL_InitSubSystem(SDL_INIT_AUDIO) != 0 {
+                log::error!("couldn't start audio with {:?}", driver);
+                return Err(Error::sdl());
+            }
+        }
+
+        Ok(Self)
+    }
+}
+
+impl Drop for Audio {
+    fn drop(&mut self) {
+        unsafe {
+            SDL_QuitSubSystem(SDL_INIT_AUDIO);
+        }
+    }
+}
+
+unsafe extern "C" fn trampoline<C: Callback>(userdata: *mut c_void, stream: *mut u8, len: i32) {
+    let callback = &mut *(userdata as *mut C);
+    let out = slice::from_raw_parts_mut(
+        stream as *mut C::Sample,
+        len as usize / size_of::<C::Sample>(),
+    );
+    callback.fill(out);
+}
+
+/// An open output device pulling samples from `C`, starts paused
+pub struct Device<C: Callback> {
+    id: SDL_AudioDeviceID,
+    spec: Spec,
+    // Heap slot so the pointer handed to SDL survives the device being moved
+    callback: Vec<C>,
+}
+
+impl<C: Callback> Device<C> {
+    pub fn open(_: &Audio, spec: Spec, callback: C) -> Result<Self, Error> {
+        let mut slot: Vec<C> = core::iter::once(callback).collect();
+
+        let desired = SDL_AudioSpec {
+            freq: spec.freq,
+            format: C::Sample::FORMAT,
+            channels: spec.channels,
+            silence: 0,
+            samples: spec.samples,
+            padding: 0,
+            size: 0,
+            callback: Some(trampoline::<C>),
+            userdata: slot.as_mut_ptr() as *mut c_void,
+        };
+        let mut obtained = desired;
+
+        // Format and channels stay fixed so the callback always sees the layout it asked for
+        let id = unsafe {
+            SDL_OpenAudioDevice(
+                core::ptr::null(),
+                0,
+                &desired,
+                &mut obtained,
+                (SDL_AUDIO_ALLOW_FREQUENCY_CHANGE | SDL_AUDIO_ALLOW_SAMPLES_CHANGE) as i32,
+            )
+        };
+        if id == 0 {
+            log::error!("couldn't open audio device for {:?}", spec);
+            return Err(Error::sdl());
+        }
+
+        let spec = Spec {
+            freq: obtained.freq,
+            channels: obtained.channels,
+            samples: obtained.samples,
+        };
+        log::info!("opened audio device {} with {:?}", id, spec);
+
+        Ok(Self {
+            id,
+            spec,
+            callback: slot,
+        })
+    }
+
+    pub fn spec(&self) -> Spec {
+        self.spec
+    }
+
+    pub fn pause(&self) {
+        unsafe { SDL_PauseAudioDevice(self.id, 1) }
+    }
+
+    pub fn resume(&self) {
+        unsafe { SDL_PauseAudioDevice(self.id, 0) }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        unsafe { SDL_GetAudioDeviceStatus(self.id) != SDL_AudioStatus::SDL_AUDIO_PLAYING }
+    }
+
+    /// Seconds of audio SDL asks for at once, the least delay between `fill` and the speaker
+    pub fn latency(&self) -> f32 {
+        self.spec.samples as f32 / self.spec.freq as f32
+    }
+
+    /// Stops the audio thread for as long as the guard lives, to touch the callback's state
+    pub fn lock(&mut self) -> Lock<'_, C> {
+        unsafe { SDL_LockAudioDevice(self.id) }
+        Lock {
+            id: self.id,
+            callback: &mut self.callback[0],
+        }
+    }
+}
+
+impl<C: Callback> Drop for Device<C> {
+    fn drop(&mut self) {
+        // Waits for the callback to return, only then is the slot freed
+        unsafe { SDL_CloseAudioDevice(self.id) }
+    }
+}
+
+pub struct Lock<'a, C> {
+    id: SDL_AudioDeviceID,
+    callback: &'a mut C,
+}
+
+impl<C> core::ops::Deref for Lock<'_, C> {
+    type Target = C;
+
+    fn deref(&self) -> &C {
+        self.callback
+    }
+}
+
+impl<C> core::ops::DerefMut for Lock<'_, C> {
+    fn deref_mut(&mut self) -> &mut C {
+        self.callback
+    }
+}
+
+impl<C> Drop for Lock<'_, C> {
+    fn drop(&mut self) {
+        unsafe { SDL_UnlockAudioDevice(self.id) }
+    }
+}
+
+/// A device fed by pushing interleaved samples from the main thread
+pub struct Queue<S: Sample> {
+    device: Device<Consumer<S>>,
+    // Dropped after the device, which is the only other user
+    ring: Vec<Ring<S>>,
+}
+
+impl<S: Sample> Queue<S> {
+    /// `frames` is the buffer size per channel
+    pub fn open(audio: &Audio, spec: Spec, frames: usize) -> Result<Self, Error> {
+        let ring: Vec<_> = core::iter::once(Ring::new(frames * spec.channels as usize)).collect();
+        let device = Device::open(audio, spec, Consumer::new(&ring[0]))?;
+
+        Ok(Self { device, ring })
+    }
+
+    /// Returns how many samples fit, the rest is for the caller to retry later
+    pub fn push(&mut self, samples: &[S]) -> usize {
+        self.ring[0].push(samples)
+    }
+
+    /// Samples waiting to be played
+    pub fn queued(&self) -> usize {
+        self.ring[0].len()
+    }
+
+    pub fn free(&self) -> usize {
+        self.ring[0].capacity() - self.ring[0].len()
+    }
+
+    /// Times the device ran dry and played silence
+    pub fn underruns(&self) -> usize {
+        self.ring[0].underruns()
+    }
+
+    pub fn spec(&self) -> Spec {
+        self.device.spec()
+    }
+
+    pub fn pause(&self) {
+        self.device.pause()
+    }
+
+    pub fn resume(&self) {
+        self.device.resume()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.device.is_paused()
+    }
+
+    /// Seconds until a sample pushed now is played
+    pub fn latency(&self) -> f32 {
+        let spec = self.device.spec();
+        let queued = self.queued() / spec.channels.max(1) as usize;
+        self.device.latency() + queued as f32 / spec.freq as f32
+    }
+}
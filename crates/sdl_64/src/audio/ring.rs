@@ -0,0 +1,104 @@
+use super::{Callback, Sample};
+use base_64::mem::Vec;
+use core::{
+    cell::UnsafeCell,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// Single producer, single consumer queue of samples. `read` and `write` only ever grow, so
+/// their difference is the fill level even once they wrap
+pub(crate) struct Ring<S> {
+    buf: Vec<UnsafeCell<S>>,
+    read: AtomicUsize,
+    write: AtomicUsize,
+    underruns: AtomicUsize,
+}
+
+// Each slot is only ever touched by one side at a time, as decided by the counters
+unsafe impl<S: Send> Sync for Ring<S> {}
+
+impl<S: Sample> Ring<S> {
+    pub(crate) fn new(capacity: usize) -> Self {
+        let mut buf = Vec::with_capacity(capacity);
+        for _ in 0..capacity {
+            buf.push(UnsafeCell::new(S::SILENCE));
+        }
+
+        Self {
+            buf,
+            read: AtomicUsize::new(0),
+            write: AtomicUsize::new(0),
+            underruns: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn capacity(&self) -> usize {
+        self.buf.len()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        let write = self.write.load(Ordering::Acquire);
+        write.wrapping_sub(self.read.load(Ordering::Acquire))
+    }
+
+    pub(crate) fn underruns(&self) -> usize {
+        self.underruns.load(Ordering::Relaxed)
+    }
+
+    /// Producer side, returns how many samples fit
+    pub(crate) fn push(&self, samples: &[S]) -> usize {
+        let write = self.write.load(Ordering::Relaxed);
+        let free = self.capacity() - write.wrapping_sub(self.read.load(Ordering::Acquire));
+        let n = samples.len().min(free);
+        for (i, &sample) in samples[..n].iter().enumerate() {
+            unsafe {
+                *self.buf[write.wrapping_add(i) % self.capacity()].get() = sample;
+            }
+        }
+
+        self.write.store(write.wrapping_add(n), Ordering::Release);
+        n
+    }
+
+    /// Consumer side, returns how many samples were available
+    pub(crate) fn pop(&self, out: &mut [S]) -> usize {
+        let read = self.read.load(Ordering::Relaxed);
+        let len = self.write.load(Ordering::Acquire).wrapping_sub(read);
+        let n = out.len().min(len);
+        for (i, sample) in out[..n].iter_mut().enumerate() {
+            unsafe {
+                *sample = *self.buf[read.wrapping_add(i) % self.capacity()].get();
+            }
+        }
+
+        self.read.store(read.wrapping_add(n), Ordering::Release);
+        n
+    }
+}
+
+/// The device side of a `Queue`, plays whatever has been pushed and silence after that
+pub struct Consumer<S> {
+    ring: *const Ring<S>,
+}
+
+// The ring outlives the device it feeds, see `Queue`
+unsafe impl<S: Send> Send for Consumer<S> {}
+
+impl<S> Consumer<S> {
+    pub(crate) fn new(ring: &Ring<S>) -> Self {
+        Self { ring }
+    }
+}
+
+impl<S: Sample> Callback for Consumer<S> {
+    type Sample = S;
+
+    fn fill(&mut self, out: &mut [S]) {
+        let ring = unsafe { &*self.ring };
+        let n = ring.pop(out);
+        if n < out.len() {
+            ring.underruns.fetch_add(1, Ordering::Relaxed);
+            out[n..].fill(S::SILENCE);
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use super::{ring::Ring, Audio, Callback, Consumer, Driver, Queue, Spec};
+use sdl2_sys::SDL_Delay;
+
+#[test]
+fn ring_wraps() {
+    let ring = Ring::new(4);
+    assert_eq!(ring.push(&[1i16, 2, 3]), 3);
+
+    let mut out = [0; 2];
+    assert_eq!(ring.pop(&mut out), 2);
+    assert_eq!(out, [1, 2]);
+
+    // Only 3 of these fit, and the write position wraps past the end
+    assert_eq!(ring.push(&[4, 5, 6, 7]), 3);
+    assert_eq!(ring.len(), 4);
+
+    let mut out = [0; 4];
+    assert_eq!(ring.pop(&mut out), 4);
+    assert_eq!(out, [3, 4, 5, 6]);
+    assert_eq!(ring.len(), 0);
+}
+
+#[test]
+fn consumer_pads_with_silence() {
+    let ring = Ring::new(8);
+    ring.push(&[10u8, 20]);
+
+    let mut consumer = Consumer::new(&ring);
+    let mut out = [0; 4];
+    consumer.fill(&mut out);
+    assert_eq!(out, [10, 20, 0x80, 0x80]);
+    assert_eq!(ring.underruns(), 1);
+
+    ring.push(&[1, 2, 3, 4]);
+    consumer.fill(&mut out);
+    assert_eq!(out, [1, 2, 3, 4]);
+    assert_eq!(ring.underruns(), 1);
+}
+
+#[test]
+#[ignore = "needs SDL2 at runtime"]
+fn dummy_driver_drains_queue() {
+    let audio = Audio::new(Driver::Dummy).unwrap();
+    let spec = Spec {
+        freq: 8000,
+        channels: 1,
+        samples: 256,
+    };
+    let mut queue = Queue::<f32>::open(&audio, spec, 4096).unwrap();
+    assert!(queue.is_paused());
+    assert_eq!(queue.spec().channels, 1);
+
+    assert_eq!(queue.push(&[0.5; 1024]), 1024);
+    assert_eq!(queue.queued(), 1024);
+    assert!(queue.latency() > 1024.0 / 8000.0);
+
+    // The dummy driver still calls back in real time, 1024 frames at 8kHz take 128ms
+    queue.resume();
+    unsafe { SDL_Delay(500) };
+    queue.pause();
+
+    assert_eq!(queue.queued(), 0);
+    assert!(queue.underruns() > 0);
+}
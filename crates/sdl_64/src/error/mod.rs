@@ -0,0 +1,78 @@
+use base_64::mem::Vec;
+use core::fmt;
+use sdl2_sys::SDL_GetError;
+
+/// An owned copy of an error string, since SDL and GL only lend theirs until the next call.
+/// Kept on the heap so the errors carrying it stay small
+#[derive(Clone)]
+pub struct Message(Vec<u8>);
+
+impl Message {
+    /// Copies up to the first nul
+    pub fn new(text: &[u8]) -> Self {
+        let len = text.iter().position(|&c| c == 0).unwrap_or(text.len());
+        let mut buf = Vec::with_capacity(len);
+        buf.extend_from_slice(&text[..len]);
+
+        Self(buf)
+    }
+
+    /// The calling thread's last SDL error
+    pub fn sdl() -> Self {
+        let text = unsafe { core::ffi::CStr::from_ptr(SDL_GetError()) };
+        Self::new(text.to_bytes())
+    }
+
+    pub fn as_str(&self) -> &str {
+        // Drivers don't promise UTF-8, keep whatever decodes
+        match core::str::from_utf8(&self.0) {
+            Ok(text) => text,
+            Err(err) => unsafe { core::str::from_utf8_unchecked(&self.0[..err.valid_up_to()]) },
+        }
+    }
+}
+
+impl fmt::Debug for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Error {
+    /// An SDL call failed, with what `SDL_GetError` said about it
+    Sdl(Message),
+    /// Row of an action table naming an input that doesn't exist
+    UnknownInput { row: usize },
+    /// Data without the input recording header
+    NotARecording,
+    /// A recording written by an incompatible version
+    RecordingVersion(u8),
+}
+
+impl Error {
+    pub(crate) fn sdl() -> Self {
+        Error::Sdl(Message::sdl())
+    }
+}
+
+#[cfg(feature = "display")]
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(feature = "display")]
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Sdl(message) => write!(f, "SDL error: {}", message),
+            Error::UnknownInput { row } => write!(f, "unknown input in binding row {}", row),
+            Error::NotARecording => f.write_str("not an input recording"),
+            Error::RecordingVersion(version) => {
+                write!(f, "unsupported input recording version {}", version)
+            }
+        }
+    }
+}
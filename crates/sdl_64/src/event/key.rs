@@ -0,0 +1,384 @@
+use core::ops::{BitAnd, BitOr, BitOrAssign};
+use sdl2_sys::*;
+
+macro_rules! codes {
+    ($name:ident { $($variant:ident = $value:expr,)* }) => {
+        #[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($variant,)*
+            Unknown(i32),
+        }
+
+        impl $name {
+            pub fn from_sdl(code: i32) -> Self {
+                match code {
+                    $(code if code == $value as i32 => $name::$variant,)*
+                    _ => $name::Unknown(code),
+                }
+            }
+
+            pub fn to_sdl(self) -> i32 {
+                match self {
+                    $($name::$variant => $value as i32,)*
+                    $name::Unknown(code) => code,
+                }
+            }
+
+            pub fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $(stringify!($variant) => Some($name::$variant),)*
+                    _ => Option::None,
+                }
+            }
+
+            pub fn name(self) -> Option<&'static str> {
+                match self {
+                    $($name::$variant => Some(stringify!($variant)),)*
+                    $name::Unknown(_) => Option::None,
+                }
+            }
+        }
+    };
+}
+
+// Virtual key, follows the active keyboard layout
+codes!(Keycode {
+    A = SDLK_a,
+    B = SDLK_b,
+    C = SDLK_c,
+    D = SDLK_d,
+    E = SDLK_e,
+    F = SDLK_f,
+    G = SDLK_g,
+    H = SDLK_h,
+    I = SDLK_i,
+    J = SDLK_j,
+    K = SDLK_k,
+    L = SDLK_l,
+    M = SDLK_m,
+    N = SDLK_n,
+    O = SDLK_o,
+    P = SDLK_p,
+    Q = SDLK_q,
+    R = SDLK_r,
+    S = SDLK_s,
+    T = SDLK_t,
+    U = SDLK_u,
+    V = SDLK_v,
+    W = SDLK_w,
+    X = SDLK_x,
+    Y = SDLK_y,
+    Z = SDLK_z,
+    Num0 = SDLK_0,
+    Num1 = SDLK_1,
+    Num2 = SDLK_2,
+    Num3 = SDLK_3,
+    Num4 = SDLK_4,
+    Num5 = SDLK_5,
+    Num6 = SDLK_6,
+    Num7 = SDLK_7,
+    Num8 = SDLK_8,
+    Num9 = SDLK_9,
+    Return = SDLK_RETURN,
+    Escape = SDLK_ESCAPE,
+    Backspace = SDLK_BACKSPACE,
+    Tab = SDLK_TAB,
+    Space = SDLK_SPACE,
+    Minus = SDLK_MINUS,
+    Equals = SDLK_EQUALS,
+    LeftBracket = SDLK_LEFTBRACKET,
+    RightBracket = SDLK_RIGHTBRACKET,
+    Backslash = SDLK_BACKSLASH,
+    Semicolon = SDLK_SEMICOLON,
+    Quote = SDLK_QUOTE,
+    Backquote = SDLK_BACKQUOTE,
+    Comma = SDLK_COMMA,
+    Period = SDLK_PERIOD,
+    Slash = SDLK_SLASH,
+    CapsLock = SDLK_CAPSLOCK,
+    F1 = SDLK_F1,
+    F2 = SDLK_F2,
+    F3 = SDLK_F3,
+    F4 = SDLK_F4,
+    F5 = SDLK_F5,
+    F6 = SDLK_F6,
+    F7 = SDLK_F7,
+    F8 = SDLK_F8,
+    F9 = SDLK_F9,
+    F10 = SDLK_F10,
+    F11 = SDLK_F11,
+    F12 = SDLK_F12,
+    F13 = SDLK_F13,
+    F14 = SDLK_F14,
+    F15 = SDLK_F15,
+    F16 = SDLK_F16,
+    F17 = SDLK_F17,
+    F18 = SDLK_F18,
+    F19 = SDLK_F19,
+    F20 = SDLK_F20,
+    F21 = SDLK_F21,
+    F22 = SDLK_F22,
+    F23 = SDLK_F23,
+    F24 = SDLK_F24,
+    PrintScreen = SDLK_PRINTSCREEN,
+    ScrollLock = SDLK_SCROLLLOCK,
+    Pause = SDLK_PAUSE,
+    Insert = SDLK_INSERT,
+    Home = SDLK_HOME,
+    PageUp = SDLK_PAGEUP,
+    Delete = SDLK_DELETE,
+    End = SDLK_END,
+    PageDown = SDLK_PAGEDOWN,
+    Right = SDLK_RIGHT,
+    Left = SDLK_LEFT,
+    Down = SDLK_DOWN,
+    Up = SDLK_UP,
+    NumLock = SDLK_NUMLOCKCLEAR,
+    KpDivide = SDLK_KP_DIVIDE,
+    KpMultiply = SDLK_KP_MULTIPLY,
+    KpMinus = SDLK_KP_MINUS,
+    KpPlus = SDLK_KP_PLUS,
+    KpEnter = SDLK_KP_ENTER,
+    Kp0 = SDLK_KP_0,
+    Kp1 = SDLK_KP_1,
+    Kp2 = SDLK_KP_2,
+    Kp3 = SDLK_KP_3,
+    Kp4 = SDLK_KP_4,
+    Kp5 = SDLK_KP_5,
+    Kp6 = SDLK_KP_6,
+    Kp7 = SDLK_KP_7,
+    Kp8 = SDLK_KP_8,
+    Kp9 = SDLK_KP_9,
+    KpPeriod = SDLK_KP_PERIOD,
+    Application = SDLK_APPLICATION,
+    LCtrl = SDLK_LCTRL,
+    LShift = SDLK_LSHIFT,
+    LAlt = SDLK_LALT,
+    LGui = SDLK_LGUI,
+    RCtrl = SDLK_RCTRL,
+    RShift = SDLK_RSHIFT,
+    RAlt = SDLK_RALT,
+    RGui = SDLK_RGUI,
+});
+
+// Physical key position, independent of layout; named after the US layout
+codes!(Scancode {
+    A = SDL_Scancode::SDL_SCANCODE_A,
+    B = SDL_Scancode::SDL_SCANCODE_B,
+    C = SDL_Scancode::SDL_SCANCODE_C,
+    D = SDL_Scancode::SDL_SCANCODE_D,
+    E = SDL_Scancode::SDL_SCANCODE_E,
+    F = SDL_Scancode::SDL_SCANCODE_F,
+    G = SDL_Scancode::SDL_SCANCODE_G,
+    H = SDL_Scancode::SDL_SCANCODE_H,
+    I = SDL_Scancode::SDL_SCANCODE_I,
+    J = SDL_Scancode::SDL_SCANCODE_J,
+    K = SDL_Scancode::SDL_SCANCODE_K,
+    L = SDL_Scancode::SDL_SCANCODE_L,
+    M = SDL_Scancode::SDL_SCANCODE_M,
+    N = SDL_Scancode::SDL_SCANCODE_N,
+    O = SDL_Scancode::SDL_SCANCODE_O,
+    P = SDL_Scancode::SDL_SCANCODE_P,
+    Q = SDL_Scancode::SDL_SCANCODE_Q,
+    R = SDL_Scancode::SDL_SCANCODE_R,
+    S = SDL_Scancode::SDL_SCANCODE_S,
+    T = SDL_Scancode::SDL_SCANCODE_T,
+    U = SDL_Scancode::SDL_SCANCODE_U,
+    V = SDL_Scancode::SDL_SCANCODE_V,
+    W = SDL_Scancode::SDL_SCANCODE_W,
+    X = SDL_Scancode::SDL_SCANCODE_X,
+    Y = SDL_Scancode::SDL_SCANCODE_Y,
+    Z = SDL_Scancode::SDL_SCANCODE_Z,
+    Num0 = SDL_Scancode::SDL_SCANCODE_0,
+    Num1 = SDL_Scancode::SDL_SCANCODE_1,
+    Num2 = SDL_Scancode::SDL_SCANCODE_2,
+    Num3 = SDL_Scancode::SDL_SCANCODE_3,
+    Num4 = SDL_Scancode::SDL_SCANCODE_4,
+    Num5 = SDL_Scancode::SDL_SCANCODE_5,
+    Num6 = SDL_Scancode::SDL_SCANCODE_6,
+    Num7 = SDL_Scancode::SDL_SCANCODE_7,
+    Num8 = SDL_Scancode::SDL_SCANCODE_8,
+    Num9 = SDL_Scancode::SDL_SCANCODE_9,
+    Return = SDL_Scancode::SDL_SCANCODE_RETURN,
+    Escape = SDL_Scancode::SDL_SCANCODE_ESCAPE,
+    Backspace = SDL_Scancode::SDL_SCANCODE_BACKSPACE,
+    Tab = SDL_Scancode::SDL_SCANCODE_TAB,
+    Space = SDL_Scancode::SDL_SCANCODE_SPACE,
+    Minus = SDL_Scancode::SDL_SCANCODE_MINUS,
+    Equals = SDL_Scancode::SDL_SCANCODE_EQUALS,
+    LeftBracket = SDL_Scancode::SDL_SCANCODE_LEFTBRACKET,
+    RightBracket = SDL_Scancode::SDL_SCANCODE_RIGHTBRACKET,
+    Backslash = SDL_Scancode::SDL_SCANCODE_BACKSLASH,
+    Semicolon = SDL_Scancode::SDL_SCANCODE_SEMICOLON,
+    Quote = SDL_Scancode::SDL_SCANCODE_APOSTROPHE,
+    Backquote = SDL_Scancode::SDL_SCANCODE_GRAVE,
+    Comma = SDL_Scancode::SDL_SCANCODE_COMMA,
+    Period = SDL_Scancode::SDL_SCANCODE_PERIOD,
+    Slash = SDL_Scancode::SDL_SCANCODE_SLASH,
+    CapsLock = SDL_Scancode::SDL_SCANCODE_CAPSLOCK,
+    F1 = SDL_Scancode::SDL_SCANCODE_F1,
+    F2 = SDL_Scancode::SDL_SCANCODE_F2,
+    F3 = SDL_Scancode::SDL_SCANCODE_F3,
+    F4 = SDL_Scancode::SDL_SCANCODE_F4,
+    F5 = SDL_Scancode::SDL_SCANCODE_F5,
+    F6 = SDL_Scancode::SDL_SCANCODE_F6,
+    F7 = SDL_Scancode::SDL_SCANCODE_F7,
+    F8 = SDL_Scancode::SDL_SCANCODE_F8,
+    F9 = SDL_Scancode::SDL_SCANCODE_F9,
+    F10 = SDL_Scancode::SDL_SCANCODE_F10,
+    F11 = SDL_Scancode::SDL_SCANCODE_F11,
+    F12 = SDL_Scancode::SDL_SCANCODE_F12,
+    F13 = SDL_Scancode::SDL_SCANCODE_F13,
+    F14 = SDL_Scancode::SDL_SCANCODE_F14,
+    F15 = SDL_Scancode::SDL_SCANCODE_F15,
+    F16 = SDL_Scancode::SDL_SCANCODE_F16,
+    F17 = SDL_Scancode::SDL_SCANCODE_F17,
+    F18 = SDL_Scancode::SDL_SCANCODE_F18,
+    F19 = SDL_Scancode::SDL_SCANCODE_F19,
+    F20 = SDL_Scancode::SDL_SCANCODE_F20,
+    F21 = SDL_Scancode::SDL_SCANCODE_F21,
+    F22 = SDL_Scancode::SDL_SCANCODE_F22,
+    F23 = SDL_Scancode::SDL_SCANCODE_F23,
+    F24 = SDL_Scancode::SDL_SCANCODE_F24,
+    PrintScreen = SDL_Scancode::SDL_SCANCODE_PRINTSCREEN,
+    ScrollLock = SDL_Scancode::SDL_SCANCODE_SCROLLLOCK,
+    Pause = SDL_Scancode::SDL_SCANCODE_PAUSE,
+    Insert = SDL_Scancode::SDL_SCANCODE_INSERT,
+    Home = SDL_Scancode::SDL_SCANCODE_HOME,
+    PageUp = SDL_Scancode::SDL_SCANCODE_PAGEUP,
+    Delete = SDL_Scancode::SDL_SCANCODE_DELETE,
+    End = SDL_Scancode::SDL_SCANCODE_END,
+    PageDown = SDL_Scancode::SDL_SCANCODE_PAGEDOWN,
+    Right = SDL_Scancode::SDL_SCANCODE_RIGHT,
+    Left = SDL_Scancode::SDL_SCANCODE_LEFT,
+    Down = SDL_Scancode::SDL_SCANCODE_DOWN,
+    Up = SDL_Scancode::SDL_SCANCODE_UP,
+    NumLock = SDL_Scancode::SDL_SCANCODE_NUMLOCKCLEAR,
+    KpDivide = SDL_Scancode::SDL_SCANCODE_KP_DIVIDE,
+    KpMultiply = SDL_Scancode::SDL_SCANCODE_KP_MULTIPLY,
+    KpMinus = SDL_Scancode::SDL_SCANCODE_KP_MINUS,
+    KpPlus = SDL_Scancode::SDL_SCANCODE_KP_PLUS,
+    KpEnter = SDL_Scancode::SDL_SCANCODE_KP_ENTER,
+    Kp0 = SDL_Scancode::SDL_SCANCODE_KP_0,
+    Kp1 = SDL_Scancode::SDL_SCANCODE_KP_1,
+    Kp2 = SDL_Scancode::SDL_SCANCODE_KP_2,
+    Kp3 = SDL_Scancode::SDL_SCANCODE_KP_3,
+    Kp4 = SDL_Scancode::SDL_SCANCODE_KP_4,
+    Kp5 = SDL_Scancode::SDL_SCANCODE_KP_5,
+    Kp6 = SDL_Scancode::SDL_SCANCODE_KP_6,
+    Kp7 = SDL_Scancode::SDL_SCANCODE_KP_7,
+    Kp8 = SDL_Scancode::SDL_SCANCODE_KP_8,
+    Kp9 = SDL_Scancode::SDL_SCANCODE_KP_9,
+    KpPeriod = SDL_Scancode::SDL_SCANCODE_KP_PERIOD,
+    Application = SDL_Scancode::SDL_SCANCODE_APPLICATION,
+    LCtrl = SDL_Scancode::SDL_SCANCODE_LCTRL,
+    LShift = SDL_Scancode::SDL_SCANCODE_LSHIFT,
+    LAlt = SDL_Scancode::SDL_SCANCODE_LALT,
+    LGui = SDL_Scancode::SDL_SCANCODE_LGUI,
+    RCtrl = SDL_Scancode::SDL_SCANCODE_RCTRL,
+    RShift = SDL_Scancode::SDL_SCANCODE_RSHIFT,
+    RAlt = SDL_Scancode::SDL_SCANCODE_RALT,
+    RGui = SDL_Scancode::SDL_SCANCODE_RGUI,
+    NonUsBackslash = SDL_Scancode::SDL_SCANCODE_NONUSBACKSLASH,
+    NonUsHash = SDL_Scancode::SDL_SCANCODE_NONUSHASH,
+});
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers(u16);
+
+impl Modifiers {
+    pub const NONE: Self = Self(0);
+    pub const LSHIFT: Self = Self(SDL_Keymod::KMOD_LSHIFT as u16);
+    pub const RSHIFT: Self = Self(SDL_Keymod::KMOD_RSHIFT as u16);
+    pub const LCTRL: Self = Self(SDL_Keymod::KMOD_LCTRL as u16);
+    pub const RCTRL: Self = Self(SDL_Keymod::KMOD_RCTRL as u16);
+    pub const LALT: Self = Self(SDL_Keymod::KMOD_LALT as u16);
+    pub const RALT: Self = Self(SDL_Keymod::KMOD_RALT as u16);
+    pub const LGUI: Self = Self(SDL_Keymod::KMOD_LGUI as u16);
+    pub const RGUI: Self = Self(SDL_Keymod::KMOD_RGUI as u16);
+    pub const NUM: Self = Self(SDL_Keymod::KMOD_NUM as u16);
+    pub const CAPS: Self = Self(SDL_Keymod::KMOD_CAPS as u16);
+    pub const MODE: Self = Self(SDL_Keymod::KMOD_MODE as u16);
+
+    pub const SHIFT: Self = Self(Self::LSHIFT.0 | Self::RSHIFT.0);
+    pub const CTRL: Self = Self(Self::LCTRL.0 | Self::RCTRL.0);
+    pub const ALT: Self = Self(Self::LALT.0 | Self::RALT.0);
+    pub const GUI: Self = Self(Self::LGUI.0 | Self::RGUI.0);
+
+    pub fn from_sdl(mod_: u16) -> Self {
+        Self(mod_)
+    }
+
+    pub fn to_sdl(self) -> u16 {
+        self.0
+    }
+
+    pub fn bits(self) -> u16 {
+        self.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    pub fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    pub fn shift(self) -> bool {
+        self.intersects(Self::SHIFT)
+    }
+
+    pub fn ctrl(self) -> bool {
+        self.intersects(Self::CTRL)
+    }
+
+    pub fn alt(self) -> bool {
+        self.intersects(Self::ALT)
+    }
+
+    pub fn gui(self) -> bool {
+        self.intersects(Self::GUI)
+    }
+
+    pub fn caps_lock(self) -> bool {
+        self.intersects(Self::CAPS)
+    }
+
+    pub fn num_lock(self) -> bool {
+        self.intersects(Self::NUM)
+    }
+
+    /// Ignores lock state and which side was pressed, so `Modifiers::CTRL` matches either ctrl key
+    pub fn matches(self, other: Self) -> bool {
+        [Self::SHIFT, Self::CTRL, Self::ALT, Self::GUI]
+            .iter()
+            .all(|&side| self.intersects(side) == other.intersects(side))
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+impl BitOrAssign for Modifiers {
+    fn bitor_assign(&mut self, rhs: Self) {
+        self.0 |= rhs.0;
+    }
+}
+
+impl BitAnd for Modifiers {
+    type Output = Self;
+
+    fn bitand(self, rhs: Self) -> Self {
+        Self(self.0 & rhs.0)
+    }
+}
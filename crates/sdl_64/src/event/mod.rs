@@ -1,26 +1,545 @@
+mod key;
+
+pub use key::{Keycode, Modifiers, Scancode};
 use log;
 use sdl2_sys::*;
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum MouseButton {
+    Left,
+    Middle,
+    Right,
+    X1,
+    X2,
+    Other(u8),
+}
+
+impl MouseButton {
+    pub fn from_sdl(button: u8) -> Self {
+        match button as u32 {
+            SDL_BUTTON_LEFT => MouseButton::Left,
+            SDL_BUTTON_MIDDLE => MouseButton::Middle,
+            SDL_BUTTON_RIGHT => MouseButton::Right,
+            SDL_BUTTON_X1 => MouseButton::X1,
+            SDL_BUTTON_X2 => MouseButton::X2,
+            _ => MouseButton::Other(button),
+        }
+    }
+
+    pub fn to_sdl(self) -> u8 {
+        match self {
+            MouseButton::Left => SDL_BUTTON_LEFT as u8,
+            MouseButton::Middle => SDL_BUTTON_MIDDLE as u8,
+            MouseButton::Right => SDL_BUTTON_RIGHT as u8,
+            MouseButton::X1 => SDL_BUTTON_X1 as u8,
+            MouseButton::X2 => SDL_BUTTON_X2 as u8,
+            MouseButton::Other(button) => button,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "Left" => Some(MouseButton::Left),
+            "Middle" => Some(MouseButton::Middle),
+            "Right" => Some(MouseButton::Right),
+            "X1" => Some(MouseButton::X1),
+            "X2" => Some(MouseButton::X2),
+            _ => Option::None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum WindowEvent {
+    Shown,
+    Hidden,
+    Exposed,
+    Moved { pos: [i32; 2] },
+    Resized { dim: [i32; 2] },
+    SizeChanged { dim: [i32; 2] },
+    Minimized,
+    Maximized,
+    Restored,
+    MouseEnter,
+    MouseLeave,
+    FocusGained,
+    FocusLost,
+    Close,
+    Other(u8),
+}
+
+impl WindowEvent {
+    fn from_sdl(
+        SDL_WindowEvent {
+            event,
+            data1,
+            data2,
+            ..
+        }: SDL_WindowEvent,
+    ) -> Self {
+        Self::from_raw(event, [data1, data2])
+    }
+
+    pub(crate) fn from_raw(event: u8, [data1, data2]: [i32; 2]) -> Self {
+        use SDL_WindowEventID::*;
+
+        const SHOWN: u8 = SDL_WINDOWEVENT_SHOWN as u8;
+        const HIDDEN: u8 = SDL_WINDOWEVENT_HIDDEN as u8;
+        const EXPOSED: u8 = SDL_WINDOWEVENT_EXPOSED as u8;
+        const MOVED: u8 = SDL_WINDOWEVENT_MOVED as u8;
+        const RESIZED: u8 = SDL_WINDOWEVENT_RESIZED as u8;
+        const SIZE_CHANGED: u8 = SDL_WINDOWEVENT_SIZE_CHANGED as u8;
+        const MINIMIZED: u8 = SDL_WINDOWEVENT_MINIMIZED as u8;
+        const MAXIMIZED: u8 = SDL_WINDOWEVENT_MAXIMIZED as u8;
+        const RESTORED: u8 = SDL_WINDOWEVENT_RESTORED as u8;
+        const ENTER: u8 = SDL_WINDOWEVENT_ENTER as u8;
+        const LEAVE: u8 = SDL_WINDOWEVENT_LEAVE as u8;
+        const FOCUS_GAINED: u8 = SDL_WINDOWEVENT_FOCUS_GAINED as u8;
+        const FOCUS_LOST: u8 = SDL_WINDOWEVENT_FOCUS_LOST as u8;
+        const CLOSE: u8 = SDL_WINDOWEVENT_CLOSE as u8;
+
+        match event {
+            SHOWN => WindowEvent::Shown,
+            HIDDEN => WindowEvent::Hidden,
+            EXPOSED => WindowEvent::Exposed,
+            MOVED => WindowEvent::Moved {
+                pos: [data1, data2],
+            },
+            RESIZED => WindowEvent::Resized {
+                dim: [data1, data2],
+            },
+            SIZE_CHANGED => WindowEvent::SizeChanged {
+                dim: [data1, data2],
+            },
+            MINIMIZED => WindowEvent::Minimized,
+            MAXIMIZED => WindowEvent::Maximized,
+            RESTORED => WindowEvent::Restored,
+            ENTER => WindowEvent::MouseEnter,
+            LEAVE => WindowEvent::MouseLeave,
+            FOCUS_GAINED => WindowEvent::FocusGained,
+            FOCUS_LOST => WindowEvent::FocusLost,
+            CLOSE => WindowEvent::Close,
+            _ => WindowEvent::Other(event),
+        }
+    }
+
+    /// The SDL event id and its two data fields
+    pub fn to_sdl(self) -> (u8, [i32; 2]) {
+        use SDL_WindowEventID::*;
+
+        match self {
+            WindowEvent::Shown => (SDL_WINDOWEVENT_SHOWN as u8, [0; 2]),
+            WindowEvent::Hidden => (SDL_WINDOWEVENT_HIDDEN as u8, [0; 2]),
+            WindowEvent::Exposed => (SDL_WINDOWEVENT_EXPOSED as u8, [0; 2]),
+            WindowEvent::Moved { pos } => (SDL_WINDOWEVENT_MOVED as u8, pos),
+            WindowEvent::Resized { dim } => (SDL_WINDOWEVENT_RESIZED as u8, dim),
+            WindowEvent::SizeChanged { dim } => (SDL_WINDOWEVENT_SIZE_CHANGED as u8, dim),
+            WindowEvent::Minimized => (SDL_WINDOWEVENT_MINIMIZED as u8, [0; 2]),
+            WindowEvent::Maximized => (SDL_WINDOWEVENT_MAXIMIZED as u8, [0; 2]),
+            WindowEvent::Restored => (SDL_WINDOWEVENT_RESTORED as u8, [0; 2]),
+            WindowEvent::MouseEnter => (SDL_WINDOWEVENT_ENTER as u8, [0; 2]),
+            WindowEvent::MouseLeave => (SDL_WINDOWEVENT_LEAVE as u8, [0; 2]),
+            WindowEvent::FocusGained => (SDL_WINDOWEVENT_FOCUS_GAINED as u8, [0; 2]),
+            WindowEvent::FocusLost => (SDL_WINDOWEVENT_FOCUS_LOST as u8, [0; 2]),
+            WindowEvent::Close => (SDL_WINDOWEVENT_CLOSE as u8, [0; 2]),
+            WindowEvent::Other(event) => (event, [0; 2]),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Axis {
+    LeftX,
+    LeftY,
+    RightX,
+    RightY,
+    TriggerLeft,
+    TriggerRight,
+    Other(u8),
+}
+
+impl Axis {
+    pub fn from_sdl(axis: u8) -> Self {
+        match axis {
+            0 => Axis::LeftX,
+            1 => Axis::LeftY,
+            2 => Axis::RightX,
+            3 => Axis::RightY,
+            4 => Axis::TriggerLeft,
+            5 => Axis::TriggerRight,
+            _ => Axis::Other(axis),
+        }
+    }
+
+    pub fn to_sdl(self) -> u8 {
+        match self {
+            Axis::LeftX => 0,
+            Axis::LeftY => 1,
+            Axis::RightX => 2,
+            Axis::RightY => 3,
+            Axis::TriggerLeft => 4,
+            Axis::TriggerRight => 5,
+            Axis::Other(axis) => axis,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        (0..6)
+            .map(Self::from_sdl)
+            .find(|axis| axis.name() == Some(name))
+    }
+
+    pub fn name(self) -> Option<&'static str> {
+        match self {
+            Axis::LeftX => Some("LeftX"),
+            Axis::LeftY => Some("LeftY"),
+            Axis::RightX => Some("RightX"),
+            Axis::RightY => Some("RightY"),
+            Axis::TriggerLeft => Some("TriggerLeft"),
+            Axis::TriggerRight => Some("TriggerRight"),
+            Axis::Other(_) => Option::None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Button {
+    A,
+    B,
+    X,
+    Y,
+    Back,
+    Guide,
+    Start,
+    LeftStick,
+    RightStick,
+    LeftShoulder,
+    RightShoulder,
+    DPadUp,
+    DPadDown,
+    DPadLeft,
+    DPadRight,
+    Other(u8),
+}
+
+impl Button {
+    pub fn from_sdl(button: u8) -> Self {
+        match button {
+            0 => Button::A,
+            1 => Button::B,
+            2 => Button::X,
+            3 => Button::Y,
+            4 => Button::Back,
+            5 => Button::Guide,
+            6 => Button::Start,
+            7 => Button::LeftStick,
+            8 => Button::RightStick,
+            9 => Button::LeftShoulder,
+            10 => Button::RightShoulder,
+            11 => Button::DPadUp,
+            12 => Button::DPadDown,
+            13 => Button::DPadLeft,
+            14 => Button::DPadRight,
+            _ => Button::Other(button),
+        }
+    }
+
+    pub fn to_sdl(self) -> u8 {
+        match self {
+            Button::A => 0,
+            Button::B => 1,
+            Button::X => 2,
+            Button::Y => 3,
+            Button::Back => 4,
+            Button::Guide => 5,
+            Button::Start => 6,
+            Button::LeftStick => 7,
+            Button::RightStick => 8,
+            Button::LeftShoulder => 9,
+            Button::RightShoulder => 10,
+            Button::DPadUp => 11,
+            Button::DPadDown => 12,
+            Button::DPadLeft => 13,
+            Button::DPadRight => 14,
+            Button::Other(button) => button,
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        (0..15)
+            .map(Self::from_sdl)
+            .find(|button| button.name() == Some(name))
+    }
+
+    pub fn name(self) -> Option<&'static str> {
+        match self {
+            Button::A => Some("A"),
+            Button::B => Some("B"),
+            Button::X => Some("X"),
+            Button::Y => Some("Y"),
+            Button::Back => Some("Back"),
+            Button::Guide => Some("Guide"),
+            Button::Start => Some("Start"),
+            Button::LeftStick => Some("LeftStick"),
+            Button::RightStick => Some("RightStick"),
+            Button::LeftShoulder => Some("LeftShoulder"),
+            Button::RightShoulder => Some("RightShoulder"),
+            Button::DPadUp => Some("DPadUp"),
+            Button::DPadDown => Some("DPadDown"),
+            Button::DPadLeft => Some("DPadLeft"),
+            Button::DPadRight => Some("DPadRight"),
+            Button::Other(_) => Option::None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
 pub enum Event {
     Quit,
     Keyboard {
         down: bool,
+        repeat: bool,
         timestamp: u32,
-        sym: i32,
-        mod_: u32,
+        key: Keycode,
+        scancode: Scancode,
+        modifiers: Modifiers,
     },
     TextInput {
         text: [i8; 32],
     },
+    MouseMotion {
+        timestamp: u32,
+        window: u32,
+        pos: [i32; 2],
+        rel: [i32; 2],
+        // Held buttons, bit n - 1 set for SDL button n
+        buttons: u32,
+    },
+    MouseButton {
+        down: bool,
+        timestamp: u32,
+        window: u32,
+        button: MouseButton,
+        clicks: u8,
+        pos: [i32; 2],
+    },
+    MouseWheel {
+        timestamp: u32,
+        window: u32,
+        // Positive y scrolls away from the user, regardless of "natural" scrolling
+        delta: [i32; 2],
+    },
+    Window {
+        timestamp: u32,
+        window: u32,
+        event: WindowEvent,
+    },
+    ControllerAxis {
+        timestamp: u32,
+        which: i32,
+        axis: Axis,
+        value: i16,
+    },
+    ControllerButton {
+        down: bool,
+        timestamp: u32,
+        which: i32,
+        button: Button,
+    },
+    // `which` is the instance id used by the other controller events, not the device index
+    ControllerAdded {
+        timestamp: u32,
+        which: i32,
+    },
+    ControllerRemoved {
+        timestamp: u32,
+        which: i32,
+    },
+    ControllerRemapped {
+        timestamp: u32,
+        which: i32,
+    },
+    // Anything not translated above, so `None` can mean the queue is empty
+    Other {
+        timestamp: u32,
+        type_: u32,
+    },
 }
 
+impl Event {
+    pub fn timestamp(&self) -> Option<u32> {
+        match *self {
+            Event::Quit | Event::TextInput { .. } => Option::None,
+            Event::Keyboard { timestamp, .. }
+            | Event::MouseMotion { timestamp, .. }
+            | Event::MouseButton { timestamp, .. }
+            | Event::MouseWheel { timestamp, .. }
+            | Event::Window { timestamp, .. }
+            | Event::ControllerAxis { timestamp, .. }
+            | Event::ControllerButton { timestamp, .. }
+            | Event::ControllerAdded { timestamp, .. }
+            | Event::ControllerRemoved { timestamp, .. }
+            | Event::ControllerRemapped { timestamp, .. }
+            | Event::Other { timestamp, .. } => Some(timestamp),
+        }
+    }
+
+    /// Rebuilds the SDL event, for pushing back onto the queue. Controller arrival and
+    /// untranslated events can't be faked without a device or their payload, so give `None`
+    pub(crate) fn to_sdl(self) -> Option<SDL_Event> {
+        let state = |down: bool| if down { SDL_PRESSED } else { SDL_RELEASED } as u8;
+
+        unsafe {
+            let mut raw: SDL_Event = core::mem::zeroed();
+            match self {
+                Event::Quit => raw.type_ = QUIT,
+                Event::Keyboard {
+                    down,
+                    repeat,
+                    timestamp,
+                    key,
+                    scancode,
+                    modifiers,
+                } => {
+                    raw.type_ = if down { KEYDOWN } else { KEYUP };
+                    raw.key.timestamp = timestamp;
+                    raw.key.state = state(down);
+                    raw.key.repeat = repeat as u8;
+                    raw.key.keysym.sym = key.to_sdl();
+                    raw.key.keysym.mod_ = modifiers.to_sdl();
+                    // Unknown scancodes aren't valid values of the bindgen enum
+                    *(core::ptr::addr_of_mut!(raw.key.keysym.scancode) as *mut i32) =
+                        scancode.to_sdl();
+                }
+                Event::TextInput { text } => {
+                    raw.type_ = TEXTINPUT;
+                    raw.text.text = text;
+                }
+                Event::MouseMotion {
+                    timestamp,
+                    window,
+                    pos,
+                    rel,
+                    buttons,
+                } => {
+                    raw.type_ = MOUSEMOTION;
+                    raw.motion.timestamp = timestamp;
+                    raw.motion.windowID = window;
+                    raw.motion.state = buttons;
+                    raw.motion.x = pos[0];
+                    raw.motion.y = pos[1];
+                    raw.motion.xrel = rel[0];
+                    raw.motion.yrel = rel[1];
+                }
+                Event::MouseButton {
+                    down,
+                    timestamp,
+                    window,
+                    button,
+                    clicks,
+                    pos,
+                } => {
+                    raw.type_ = if down { MOUSEBUTTONDOWN } else { MOUSEBUTTONUP };
+                    raw.button.timestamp = timestamp;
+                    raw.button.windowID = window;
+                    raw.button.button = button.to_sdl();
+                    raw.button.state = state(down);
+                    raw.button.clicks = clicks;
+                    raw.button.x = pos[0];
+                    raw.button.y = pos[1];
+                }
+                Event::MouseWheel {
+                    timestamp,
+                    window,
+                    delta,
+                } => {
+                    raw.type_ = MOUSEWHEEL;
+                    raw.wheel.timestamp = timestamp;
+                    raw.wheel.windowID = window;
+                    raw.wheel.x = delta[0];
+                    raw.wheel.y = delta[1];
+                }
+                Event::Window {
+                    timestamp,
+                    window,
+                    event,
+                } => {
+                    let (event, [data1, data2]) = event.to_sdl();
+                    raw.type_ = WINDOWEVENT;
+                    raw.window.timestamp = timestamp;
+                    raw.window.windowID = window;
+                    raw.window.event = event;
+                    raw.window.data1 = data1;
+                    raw.window.data2 = data2;
+                }
+                Event::ControllerAxis {
+                    timestamp,
+                    which,
+                    axis,
+                    value,
+                } => {
+                    raw.type_ = CONTROLLERAXISMOTION;
+                    raw.caxis.timestamp = timestamp;
+                    raw.caxis.which = which;
+                    raw.caxis.axis = axis.to_sdl();
+                    raw.caxis.value = value;
+                }
+                Event::ControllerButton {
+                    down,
+                    timestamp,
+                    which,
+                    button,
+                } => {
+                    raw.type_ = if down {
+                        CONTROLLERBUTTONDOWN
+                    } else {
+                        CONTROLLERBUTTONUP
+                    };
+                    raw.cbutton.timestamp = timestamp;
+                    raw.cbutton.which = which;
+                    raw.cbutton.button = button.to_sdl();
+                    raw.cbutton.state = state(down);
+                }
+                Event::ControllerRemoved { timestamp, which }
+                | Event::ControllerRemapped { timestamp, which } => {
+                    raw.type_ = match self {
+                        Event::ControllerRemoved { .. } => CONTROLLERDEVICEREMOVED,
+                        _ => CONTROLLERDEVICEREMAPPED,
+                    };
+                    raw.cdevice.timestamp = timestamp;
+                    raw.cdevice.which = which;
+                }
+                Event::ControllerAdded { .. } | Event::Other { .. } => return Option::None,
+            }
+
+            Some(raw)
+        }
+    }
+}
+
+const QUIT: u32 = SDL_EventType::SDL_QUIT as u32;
+const KEYDOWN: u32 = SDL_EventType::SDL_KEYDOWN as u32;
+const KEYUP: u32 = SDL_EventType::SDL_KEYUP as u32;
+const TEXTINPUT: u32 = SDL_EventType::SDL_TEXTINPUT as u32;
+const WINDOWEVENT: u32 = SDL_EventType::SDL_WINDOWEVENT as u32;
+const MOUSEMOTION: u32 = SDL_EventType::SDL_MOUSEMOTION as u32;
+const MOUSEBUTTONDOWN: u32 = SDL_EventType::SDL_MOUSEBUTTONDOWN as u32;
+const MOUSEBUTTONUP: u32 = SDL_EventType::SDL_MOUSEBUTTONUP as u32;
+const MOUSEWHEEL: u32 = SDL_EventType::SDL_MOUSEWHEEL as u32;
+const CONTROLLERAXISMOTION: u32 = SDL_EventType::SDL_CONTROLLERAXISMOTION as u32;
+const CONTROLLERBUTTONDOWN: u32 = SDL_EventType::SDL_CONTROLLERBUTTONDOWN as u32;
+const CONTROLLERBUTTONUP: u32 = SDL_EventType::SDL_CONTROLLERBUTTONUP as u32;
+const CONTROLLERDEVICEADDED: u32 = SDL_EventType::SDL_CONTROLLERDEVICEADDED as u32;
+const CONTROLLERDEVICEREMOVED: u32 = SDL_EventType::SDL_CONTROLLERDEVICEREMOVED as u32;
+const CONTROLLERDEVICEREMAPPED: u32 = SDL_EventType::SDL_CONTROLLERDEVICEREMAPPED as u32;
+
 pub struct EventFeed;
 
 impl EventFeed {
     pub fn new() -> Self {
         unsafe {
-            SDL_InitSubSystem(SDL_INIT_EVENTS);
+            // Controllers already plugged in are reported as added once the subsystem is up
+            SDL_InitSubSystem(SDL_INIT_EVENTS | SDL_INIT_GAMECONTROLLER);
         }
 
         Self {}
@@ -35,6 +554,32 @@ impl EventFeed {
             }
         }
     }
+
+    unsafe fn open_controller(index: i32) -> Option<i32> {
+        let controller = SDL_GameControllerOpen(index);
+        if controller.is_null() {
+            log::warn!("failed to open controller {}", index);
+            return Option::None;
+        }
+
+        let id = SDL_JoystickInstanceID(SDL_GameControllerGetJoystick(controller));
+        log::info!("opened controller {} as instance {}", index, id);
+        Some(id)
+    }
+
+    unsafe fn close_controller(id: i32) {
+        let controller = SDL_GameControllerFromInstanceID(id);
+        if !controller.is_null() {
+            log::info!("closing controller instance {}", id);
+            SDL_GameControllerClose(controller);
+        }
+    }
+}
+
+impl Default for EventFeed {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Iterator for EventFeed {
@@ -44,44 +589,184 @@ impl Iterator for EventFeed {
         unsafe {
             log::trace!("polling event");
             let mut event = core::mem::MaybeUninit::uninit();
-            if SDL_PollEvent(event.as_mut_ptr()) == 1 {
-                let event = event.assume_init();
-                log::trace!("event type {:?} receieved", event.type_);
-                match core::mem::transmute(event.type_) {
-                    SDL_EventType::SDL_QUIT => Some(Event::Quit),
-                    SDL_EventType::SDL_KEYDOWN | SDL_EventType::SDL_KEYUP => {
-                        let SDL_KeyboardEvent {
-                            type_,
-                            timestamp,
-                            keysym: SDL_Keysym { sym, mod_, .. },
-                            ..
-                        } = event.key;
-
-                        let down = match core::mem::transmute(type_) {
-                            SDL_EventType::SDL_KEYDOWN => true,
-                            SDL_EventType::SDL_KEYUP => false,
-                            _ => panic!(),
+            if SDL_PollEvent(event.as_mut_ptr()) != 1 {
+                return Option::None;
+            }
+
+            let event = event.assume_init();
+            log::trace!("event type {:?} receieved", event.type_);
+            Some(match event.type_ {
+                QUIT => Event::Quit,
+                KEYDOWN | KEYUP => {
+                    let SDL_KeyboardEvent {
+                        type_,
+                        timestamp,
+                        repeat,
+                        keysym: SDL_Keysym { sym, mod_, .. },
+                        ..
+                    } = event.key;
+                    // Not bound through the pattern, SDL sends scancodes the bindgen enum lacks
+                    let scancode = *(core::ptr::addr_of!(event.key.keysym.scancode) as *const i32);
+
+                    Event::Keyboard {
+                        down: type_ == KEYDOWN,
+                        repeat: repeat != 0,
+                        timestamp,
+                        key: Keycode::from_sdl(sym),
+                        scancode: Scancode::from_sdl(scancode),
+                        modifiers: Modifiers::from_sdl(mod_),
+                    }
+                }
+
+                TEXTINPUT => {
+                    let SDL_TextInputEvent { text, .. } = event.text;
+
+                    Event::TextInput { text: text }
+                }
+
+                WINDOWEVENT => Event::Window {
+                    timestamp: event.window.timestamp,
+                    window: event.window.windowID,
+                    event: WindowEvent::from_sdl(event.window),
+                },
+
+                MOUSEMOTION => {
+                    let SDL_MouseMotionEvent {
+                        timestamp,
+                        windowID,
+                        state,
+                        x,
+                        y,
+                        xrel,
+                        yrel,
+                        ..
+                    } = event.motion;
+
+                    Event::MouseMotion {
+                        timestamp,
+                        window: windowID,
+                        pos: [x, y],
+                        rel: [xrel, yrel],
+                        buttons: state,
+                    }
+                }
+
+                MOUSEBUTTONDOWN | MOUSEBUTTONUP => {
+                    let SDL_MouseButtonEvent {
+                        type_,
+                        timestamp,
+                        windowID,
+                        button,
+                        clicks,
+                        x,
+                        y,
+                        ..
+                    } = event.button;
+
+                    Event::MouseButton {
+                        down: type_ == MOUSEBUTTONDOWN,
+                        timestamp,
+                        window: windowID,
+                        button: MouseButton::from_sdl(button),
+                        clicks,
+                        pos: [x, y],
+                    }
+                }
+
+                MOUSEWHEEL => {
+                    let SDL_MouseWheelEvent {
+                        timestamp,
+                        windowID,
+                        x,
+                        y,
+                        direction,
+                        ..
+                    } = event.wheel;
+
+                    let flip =
+                        if direction == SDL_MouseWheelDirection::SDL_MOUSEWHEEL_FLIPPED as u32 {
+                            -1
+                        } else {
+                            1
                         };
+                    Event::MouseWheel {
+                        timestamp,
+                        window: windowID,
+                        delta: [x * flip, y * flip],
+                    }
+                }
 
-                        Some(Event::Keyboard {
-                            down,
-                            timestamp,
-                            sym,
-                            mod_: mod_ as _,
-                        })
+                CONTROLLERAXISMOTION => {
+                    let SDL_ControllerAxisEvent {
+                        timestamp,
+                        which,
+                        axis,
+                        value,
+                        ..
+                    } = event.caxis;
+
+                    Event::ControllerAxis {
+                        timestamp,
+                        which,
+                        axis: Axis::from_sdl(axis),
+                        value,
                     }
+                }
+
+                CONTROLLERBUTTONDOWN | CONTROLLERBUTTONUP => {
+                    let SDL_ControllerButtonEvent {
+                        type_,
+                        timestamp,
+                        which,
+                        button,
+                        ..
+                    } = event.cbutton;
+
+                    Event::ControllerButton {
+                        down: type_ == CONTROLLERBUTTONDOWN,
+                        timestamp,
+                        which,
+                        button: Button::from_sdl(button),
+                    }
+                }
 
-                    SDL_EventType::SDL_TEXTINPUT => {
-                        let SDL_TextInputEvent { text, .. } = event.text;
+                CONTROLLERDEVICEADDED => {
+                    let SDL_ControllerDeviceEvent {
+                        timestamp, which, ..
+                    } = event.cdevice;
 
-                        Some(Event::TextInput { text: text })
+                    // Added events carry the device index; everything after uses the instance id
+                    match Self::open_controller(which) {
+                        Some(which) => Event::ControllerAdded { timestamp, which },
+                        Option::None => Event::Other {
+                            timestamp,
+                            type_: CONTROLLERDEVICEADDED,
+                        },
                     }
+                }
+
+                CONTROLLERDEVICEREMOVED => {
+                    let SDL_ControllerDeviceEvent {
+                        timestamp, which, ..
+                    } = event.cdevice;
 
-                    _ => Option::None,
+                    Self::close_controller(which);
+                    Event::ControllerRemoved { timestamp, which }
                 }
-            } else {
-                Option::None
-            }
+
+                CONTROLLERDEVICEREMAPPED => {
+                    let SDL_ControllerDeviceEvent {
+                        timestamp, which, ..
+                    } = event.cdevice;
+
+                    Event::ControllerRemapped { timestamp, which }
+                }
+
+                type_ => Event::Other {
+                    timestamp: event.common.timestamp,
+                    type_,
+                },
+            })
         }
     }
 }
@@ -89,7 +774,8 @@ impl Iterator for EventFeed {
 impl Drop for EventFeed {
     fn drop(&mut self) {
         unsafe {
-            SDL_QuitSubSystem(SDL_INIT_EVENTS);
+            // Also closes any controllers still open
+            SDL_QuitSubSystem(SDL_INIT_EVENTS | SDL_INIT_GAMECONTROLLER);
         }
     }
 }
@@ -0,0 +1,175 @@
+use super::{Input, InputState};
+use crate::{event::Axis, Error};
+use base_64::mem::Vec;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum AxisInput {
+    Analog(Axis),
+    Digital { negative: Input, positive: Input },
+}
+
+impl AxisInput {
+    /// Parses `"pad:LeftX"` or a `"keys:Left,Right"` pair of `Input` specs
+    pub fn parse(spec: &str) -> Option<Self> {
+        match spec.split_once(':')? {
+            ("pad", name) => Axis::from_name(name).map(AxisInput::Analog),
+            ("keys", pair) => {
+                let (negative, positive) = pair.split_once(',')?;
+                Some(AxisInput::Digital {
+                    negative: Input::parse(negative.trim())?,
+                    positive: Input::parse(positive.trim())?,
+                })
+            }
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Binding {
+    Button(Input),
+    Axis(AxisInput),
+}
+
+impl Binding {
+    pub fn parse(spec: &str) -> Option<Self> {
+        AxisInput::parse(spec)
+            .map(Binding::Axis)
+            .or_else(|| Input::parse(spec).map(Binding::Button))
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ActionMap {
+    // Each distinct name once, packed, so tables read at runtime needn't outlive the map
+    names: Vec<u8>,
+    actions: Vec<(usize, usize)>,
+    bindings: Vec<(usize, Binding)>,
+    deadzone: f32,
+}
+
+impl ActionMap {
+    pub fn new() -> Self {
+        Self {
+            names: Vec::new(),
+            actions: Vec::new(),
+            bindings: Vec::new(),
+            deadzone: 0.2,
+        }
+    }
+
+    /// Builds a map from `(name, spec)` rows, e.g. `("jump", "Space")`, `("jump", "pad:A")`,
+    /// `("move_x", "pad:LeftX")` or `("move_x", "keys:A,D")`; a name may appear on several rows
+    pub fn from_table(table: &[(&str, &str)]) -> Result<Self, Error> {
+        let mut map = Self::new();
+        for (row, &(name, spec)) in table.iter().enumerate() {
+            match Binding::parse(spec) {
+                Some(binding) => map.bind(name, binding),
+                None => {
+                    log::error!("can't bind '{}' to unknown input '{}'", name, spec);
+                    return Err(Error::UnknownInput { row });
+                }
+            }
+        }
+
+        Ok(map)
+    }
+
+    pub fn set_deadzone(&mut self, deadzone: f32) {
+        self.deadzone = deadzone;
+    }
+
+    pub fn bind(&mut self, name: &str, binding: Binding) {
+        let action = match self.action(name) {
+            Some(action) => action,
+            None => {
+                let start = self.names.len();
+                self.names.extend_from_slice(name.as_bytes());
+                self.actions.push((start, name.len()));
+                self.actions.len() - 1
+            }
+        };
+        self.bindings.push((action, binding));
+    }
+
+    /// Removes every binding of `name`
+    pub fn unbind(&mut self, name: &str) {
+        if let Some(action) = self.action(name) {
+            self.bindings.retain(|&(bound, _)| bound != action);
+        }
+    }
+
+    /// Replaces every binding of `name` with `binding`
+    pub fn rebind(&mut self, name: &str, binding: Binding) {
+        self.unbind(name);
+        self.bind(name, binding);
+    }
+
+    pub fn bindings(&self, name: &str) -> impl Iterator<Item = Binding> + '_ {
+        let action = self.action(name);
+        self.bindings
+            .iter()
+            .filter(move |&&(bound, _)| Some(bound) == action)
+            .map(|&(_, binding)| binding)
+    }
+
+    fn action(&self, name: &str) -> Option<usize> {
+        self.actions
+            .iter()
+            .position(|&(start, len)| &self.names[start..start + len] == name.as_bytes())
+    }
+
+    fn buttons(&self, name: &str) -> impl Iterator<Item = Input> + '_ {
+        self.bindings(name).filter_map(|binding| match binding {
+            Binding::Button(input) => Some(input),
+            Binding::Axis(_) => None,
+        })
+    }
+
+    pub fn held(&self, input: &InputState, name: &str) -> bool {
+        self.buttons(name).any(|button| input.held(button))
+    }
+
+    /// Only once the first binding goes down, not again when another joins it
+    pub fn pressed(&self, input: &InputState, name: &str) -> bool {
+        self.buttons(name).any(|button| input.pressed(button))
+            && !self.buttons(name).any(|button| held_before(input, button))
+    }
+
+    /// Only once the last held binding lets go
+    pub fn released(&self, input: &InputState, name: &str) -> bool {
+        self.buttons(name).any(|button| input.released(button)) && !self.held(input, name)
+    }
+
+    /// Sum of all axis bindings of `name`, clamped to -1..1
+    pub fn axis(&self, input: &InputState, name: &str) -> f32 {
+        self.bindings(name)
+            .filter_map(|binding| match binding {
+                Binding::Axis(AxisInput::Analog(axis)) => {
+                    let value = input.axis(axis);
+                    Some(if value > self.deadzone || value < -self.deadzone {
+                        value
+                    } else {
+                        0.0
+                    })
+                }
+                Binding::Axis(AxisInput::Digital { negative, positive }) => {
+                    Some(input.held(positive) as i32 as f32 - input.held(negative) as i32 as f32)
+                }
+                Binding::Button(_) => None,
+            })
+            .sum::<f32>()
+            .clamp(-1.0, 1.0)
+    }
+}
+
+impl Default for ActionMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether `button` was already down going into this frame
+fn held_before(input: &InputState, button: Input) -> bool {
+    !input.pressed(button) && (input.held(button) || input.released(button))
+}
@@ -0,0 +1,365 @@
+mod action;
+
+use crate::event::{Axis, Button, Event, Keycode, Modifiers, MouseButton, Scancode, WindowEvent};
+pub use action::{ActionMap, AxisInput, Binding};
+use sdl2_sys::{SDL_GetScancodeFromKey, SDL_Scancode};
+
+const KEY_WORDS: usize = SDL_Scancode::SDL_NUM_SCANCODES as usize / 64;
+const MAX_CONTROLLERS: usize = 4;
+const AXES: usize = 6;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Input {
+    Key(Scancode),
+    Mouse(MouseButton),
+    Button(Button),
+}
+
+impl Input {
+    /// Parses `"Space"`, `"mouse:Left"` or `"pad:A"`; keys use `Scancode` names
+    pub fn parse(spec: &str) -> Option<Self> {
+        match spec.split_once(':') {
+            Some(("mouse", name)) => MouseButton::from_name(name).map(Input::Mouse),
+            Some(("pad", name)) => Button::from_name(name).map(Input::Button),
+            Some(("key", name)) | Some(("", name)) => Scancode::from_name(name).map(Input::Key),
+            Some(_) => None,
+            None => Scancode::from_name(spec).map(Input::Key),
+        }
+    }
+}
+
+impl From<Scancode> for Input {
+    fn from(scancode: Scancode) -> Self {
+        Input::Key(scancode)
+    }
+}
+
+// Resolved through the current keyboard layout
+impl From<Keycode> for Input {
+    fn from(key: Keycode) -> Self {
+        let scancode = unsafe { SDL_GetScancodeFromKey(key.to_sdl()) };
+        Input::Key(Scancode::from_sdl(scancode as i32))
+    }
+}
+
+impl From<MouseButton> for Input {
+    fn from(button: MouseButton) -> Self {
+        Input::Mouse(button)
+    }
+}
+
+impl From<Button> for Input {
+    fn from(button: Button) -> Self {
+        Input::Button(button)
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+enum State {
+    Held,
+    Pressed,
+    Released,
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Buttons<const W: usize> {
+    held: [u64; W],
+    pressed: [u64; W],
+    released: [u64; W],
+}
+
+impl<const W: usize> Buttons<W> {
+    fn new() -> Self {
+        Self {
+            held: [0; W],
+            pressed: [0; W],
+            released: [0; W],
+        }
+    }
+
+    fn begin_frame(&mut self) {
+        self.pressed = [0; W];
+        self.released = [0; W];
+    }
+
+    fn set(&mut self, idx: usize, down: bool) {
+        if idx >= W * 64 {
+            return;
+        }
+
+        let (word, bit) = (idx / 64, 1 << (idx % 64));
+        let was_held = self.held[word] & bit != 0;
+        if down && !was_held {
+            self.pressed[word] |= bit;
+            self.held[word] |= bit;
+        } else if !down && was_held {
+            self.released[word] |= bit;
+            self.held[word] &= !bit;
+        }
+    }
+
+    fn release_all(&mut self) {
+        for word in 0..W {
+            self.released[word] |= self.held[word];
+            self.held[word] = 0;
+        }
+    }
+
+    fn get(&self, state: State, idx: usize) -> bool {
+        let bits = match state {
+            State::Held => &self.held,
+            State::Pressed => &self.pressed,
+            State::Released => &self.released,
+        };
+
+        idx < W * 64 && bits[idx / 64] & (1 << (idx % 64)) != 0
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Controller {
+    id: i32,
+    buttons: Buttons<1>,
+    axes: [i16; AXES],
+}
+
+#[derive(Debug, Clone)]
+pub struct InputState {
+    keys: Buttons<KEY_WORDS>,
+    mouse: Buttons<1>,
+    controllers: [Option<Controller>; MAX_CONTROLLERS],
+    modifiers: Modifiers,
+    mouse_pos: [i32; 2],
+    mouse_delta: [i32; 2],
+    wheel: [i32; 2],
+}
+
+impl InputState {
+    pub fn new() -> Self {
+        Self {
+            keys: Buttons::new(),
+            mouse: Buttons::new(),
+            controllers: [None; MAX_CONTROLLERS],
+            modifiers: Modifiers::NONE,
+            mouse_pos: [0; 2],
+            mouse_delta: [0; 2],
+            wheel: [0; 2],
+        }
+    }
+
+    /// Starts a new frame and drains `events` into it, passing each event on to `f`
+    pub fn update(&mut self, events: impl Iterator<Item = Event>, mut f: impl FnMut(&Event)) {
+        self.begin_frame();
+        for event in events {
+            self.handle(&event);
+            f(&event);
+        }
+    }
+
+    /// Forgets this frame's presses, releases and relative motion
+    pub fn begin_frame(&mut self) {
+        self.keys.begin_frame();
+        self.mouse.begin_frame();
+        for controller in self.controllers.iter_mut().flatten() {
+            controller.buttons.begin_frame();
+        }
+        self.mouse_delta = [0; 2];
+        self.wheel = [0; 2];
+    }
+
+    pub fn handle(&mut self, event: &Event) {
+        match *event {
+            Event::Keyboard {
+                down,
+                scancode,
+                modifiers,
+                ..
+            } => {
+                self.keys.set(scancode.to_sdl() as usize, down);
+                self.modifiers = modifiers;
+            }
+            Event::MouseMotion { pos, rel, .. } => {
+                self.mouse_pos = pos;
+                self.mouse_delta = [self.mouse_delta[0] + rel[0], self.mouse_delta[1] + rel[1]];
+            }
+            Event::MouseButton {
+                down, button, pos, ..
+            } => {
+                self.mouse.set(button.to_sdl() as usize, down);
+                self.mouse_pos = pos;
+            }
+            Event::MouseWheel { delta, .. } => {
+                self.wheel = [self.wheel[0] + delta[0], self.wheel[1] + delta[1]];
+            }
+            // Key ups go to whichever window has focus now, so nothing would release them
+            Event::Window {
+                event: WindowEvent::FocusLost,
+                ..
+            } => {
+                self.keys.release_all();
+                self.mouse.release_all();
+                self.modifiers = Modifiers::NONE;
+            }
+            Event::ControllerAdded { which, .. } => {
+                self.controller_mut(which);
+            }
+            Event::ControllerRemoved { which, .. } => {
+                for slot in self.controllers.iter_mut() {
+                    if slot.is_some_and(|controller| controller.id == which) {
+                        *slot = None;
+                    }
+                }
+            }
+            Event::ControllerButton {
+                down,
+                which,
+                button,
+                ..
+            } => {
+                if let Some(controller) = self.controller_mut(which) {
+                    controller.buttons.set(button.to_sdl() as usize, down);
+                }
+            }
+            Event::ControllerAxis {
+                which, axis, value, ..
+            } => {
+                if let Some(controller) = self.controller_mut(which) {
+                    if let Some(slot) = controller.axes.get_mut(axis.to_sdl() as usize) {
+                        *slot = value;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn controller_mut(&mut self, id: i32) -> Option<&mut Controller> {
+        let idx = match self.controller_slot(id) {
+            Some(idx) => idx,
+            None => {
+                let idx = self.controllers.iter().position(Option::is_none);
+                if idx.is_none() {
+                    log::warn!("more than {} controllers, ignoring {}", MAX_CONTROLLERS, id);
+                }
+                let idx = idx?;
+                self.controllers[idx] = Some(Controller {
+                    id,
+                    buttons: Buttons::new(),
+                    axes: [0; AXES],
+                });
+                idx
+            }
+        };
+
+        self.controllers[idx].as_mut()
+    }
+
+    fn controller_slot(&self, id: i32) -> Option<usize> {
+        self.controllers
+            .iter()
+            .position(|slot| slot.is_some_and(|controller| controller.id == id))
+    }
+
+    fn query(&self, input: Input, state: State) -> bool {
+        match input {
+            Input::Key(scancode) => self.keys.get(state, scancode.to_sdl() as usize),
+            Input::Mouse(button) => self.mouse.get(state, button.to_sdl() as usize),
+            Input::Button(button) => self
+                .controllers
+                .iter()
+                .flatten()
+                .any(|controller| controller.buttons.get(state, button.to_sdl() as usize)),
+        }
+    }
+
+    pub fn held(&self, input: impl Into<Input>) -> bool {
+        self.query(input.into(), State::Held)
+    }
+
+    pub fn pressed(&self, input: impl Into<Input>) -> bool {
+        self.query(input.into(), State::Pressed)
+    }
+
+    pub fn released(&self, input: impl Into<Input>) -> bool {
+        self.query(input.into(), State::Released)
+    }
+
+    pub fn modifiers(&self) -> Modifiers {
+        self.modifiers
+    }
+
+    pub fn mouse_pos(&self) -> [i32; 2] {
+        self.mouse_pos
+    }
+
+    pub fn mouse_delta(&self) -> [i32; 2] {
+        self.mouse_delta
+    }
+
+    pub fn wheel(&self) -> [i32; 2] {
+        self.wheel
+    }
+
+    /// Instance ids of the tracked controllers, as reported by controller events
+    pub fn controllers(&self) -> impl Iterator<Item = i32> + '_ {
+        self.controllers
+            .iter()
+            .flatten()
+            .map(|controller| controller.id)
+    }
+
+    pub fn controller_held(&self, which: i32, button: Button) -> bool {
+        self.controller_slot(which)
+            .and_then(|idx| self.controllers[idx])
+            .is_some_and(|controller| {
+                controller
+                    .buttons
+                    .get(State::Held, button.to_sdl() as usize)
+            })
+    }
+
+    /// -1..1 for sticks, 0..1 for triggers
+    pub fn controller_axis(&self, which: i32, axis: Axis) -> f32 {
+        self.controller_slot(which)
+            .and_then(|idx| self.controllers[idx])
+            .and_then(|controller| controller.axes.get(axis.to_sdl() as usize).copied())
+            .map_or(0.0, normalize)
+    }
+
+    /// The strongest deflection of `axis` over all controllers
+    pub fn axis(&self, axis: Axis) -> f32 {
+        self.controllers
+            .iter()
+            .flatten()
+            .filter_map(|controller| controller.axes.get(axis.to_sdl() as usize).copied())
+            .map(normalize)
+            .fold(0.0, |strongest, value| {
+                if abs(value) > abs(strongest) {
+                    value
+                } else {
+                    strongest
+                }
+            })
+    }
+}
+
+impl Default for InputState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn normalize(value: i16) -> f32 {
+    (value as f32 / i16::MAX as f32).max(-1.0)
+}
+
+fn abs(x: f32) -> f32 {
+    if x < 0.0 {
+        -x
+    } else {
+        x
+    }
+}
+
+#[cfg(test)]
+mod tests;
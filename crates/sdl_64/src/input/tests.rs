@@ -0,0 +1,214 @@
+use super::{ActionMap, AxisInput, Binding, Input, InputState};
+use crate::{
+    event::{Axis, Button, Event, Keycode, Modifiers, MouseButton, Scancode, WindowEvent},
+    Error,
+};
+
+fn key(scancode: Scancode, down: bool) -> Event {
+    Event::Keyboard {
+        down,
+        repeat: false,
+        timestamp: 0,
+        key: Keycode::Unknown(0),
+        scancode,
+        modifiers: Modifiers::NONE,
+    }
+}
+
+fn mouse(button: MouseButton, down: bool) -> Event {
+    Event::MouseButton {
+        down,
+        timestamp: 0,
+        window: 0,
+        button,
+        clicks: 1,
+        pos: [0; 2],
+    }
+}
+
+fn stick(value: i16) -> Event {
+    Event::ControllerAxis {
+        timestamp: 0,
+        which: 0,
+        axis: Axis::LeftX,
+        value,
+    }
+}
+
+fn frame(input: &mut InputState, events: &[Event]) {
+    input.update(events.iter().copied(), |_| {});
+}
+
+#[test]
+fn pressed_held_released() {
+    let mut input = InputState::new();
+    frame(&mut input, &[key(Scancode::Space, true)]);
+    assert!(input.pressed(Scancode::Space));
+    assert!(input.held(Scancode::Space));
+    assert!(!input.released(Scancode::Space));
+
+    frame(&mut input, &[]);
+    assert!(!input.pressed(Scancode::Space));
+    assert!(input.held(Scancode::Space));
+
+    frame(&mut input, &[key(Scancode::Space, false)]);
+    assert!(input.released(Scancode::Space));
+    assert!(!input.held(Scancode::Space));
+
+    frame(&mut input, &[]);
+    assert!(!input.released(Scancode::Space));
+}
+
+#[test]
+fn tap_within_a_frame() {
+    let mut input = InputState::new();
+    frame(
+        &mut input,
+        &[
+            mouse(MouseButton::Left, true),
+            mouse(MouseButton::Left, false),
+        ],
+    );
+    assert!(input.pressed(MouseButton::Left));
+    assert!(input.released(MouseButton::Left));
+    assert!(!input.held(MouseButton::Left));
+}
+
+#[test]
+fn focus_loss_releases_everything() {
+    let mut input = InputState::new();
+    frame(
+        &mut input,
+        &[key(Scancode::A, true), mouse(MouseButton::Right, true)],
+    );
+    frame(
+        &mut input,
+        &[Event::Window {
+            timestamp: 0,
+            window: 0,
+            event: WindowEvent::FocusLost,
+        }],
+    );
+    assert!(!input.held(Scancode::A));
+    assert!(input.released(Scancode::A));
+    assert!(!input.held(MouseButton::Right));
+    assert!(input.released(MouseButton::Right));
+}
+
+#[test]
+fn parse() {
+    assert_eq!(Input::parse("Space"), Some(Input::Key(Scancode::Space)));
+    assert_eq!(Input::parse("key:A"), Some(Input::Key(Scancode::A)));
+    assert_eq!(
+        Input::parse("mouse:Left"),
+        Some(Input::Mouse(MouseButton::Left))
+    );
+    assert_eq!(Input::parse("pad:A"), Some(Input::Button(Button::A)));
+
+    assert_eq!(Input::parse("Spacebar"), None);
+    assert_eq!(Input::parse("mouse:Up"), None);
+    assert_eq!(Input::parse("pad:Z"), None);
+    assert_eq!(Input::parse("joy:A"), None);
+
+    assert_eq!(
+        Binding::parse("keys:Left, Right"),
+        Some(Binding::Axis(AxisInput::Digital {
+            negative: Input::Key(Scancode::Left),
+            positive: Input::Key(Scancode::Right),
+        }))
+    );
+    assert_eq!(
+        Binding::parse("pad:LeftX"),
+        Some(Binding::Axis(AxisInput::Analog(Axis::LeftX)))
+    );
+    assert_eq!(Binding::parse("keys:Left"), None);
+    assert_eq!(Binding::parse("keys:Left,Nowhere"), None);
+    assert_eq!(Binding::parse("pad:LeftZ"), None);
+}
+
+#[test]
+fn table_errors_name_the_row() {
+    let table = [("jump", "Space"), ("fire", "mouse:Left"), ("dash", "Shift")];
+    assert!(matches!(
+        ActionMap::from_table(&table),
+        Err(Error::UnknownInput { row: 2 })
+    ));
+    assert!(ActionMap::from_table(&table[..2]).is_ok());
+}
+
+#[test]
+fn actions() {
+    let map = ActionMap::from_table(&[("jump", "Space"), ("jump", "pad:A")]).unwrap();
+    let mut input = InputState::new();
+
+    frame(&mut input, &[key(Scancode::Space, true)]);
+    assert!(map.pressed(&input, "jump"));
+    assert!(map.held(&input, "jump"));
+
+    // A second binding joining one already held is no new press
+    frame(
+        &mut input,
+        &[Event::ControllerButton {
+            down: true,
+            timestamp: 0,
+            which: 0,
+            button: Button::A,
+        }],
+    );
+    assert!(!map.pressed(&input, "jump"));
+
+    // Nor is letting go of one while the other stays down a release
+    frame(&mut input, &[key(Scancode::Space, false)]);
+    assert!(!map.released(&input, "jump"));
+    assert!(map.held(&input, "jump"));
+
+    frame(
+        &mut input,
+        &[Event::ControllerButton {
+            down: false,
+            timestamp: 0,
+            which: 0,
+            button: Button::A,
+        }],
+    );
+    assert!(map.released(&input, "jump"));
+    assert!(!map.held(&input, "unbound"));
+}
+
+#[test]
+fn rebind() {
+    let mut map = ActionMap::from_table(&[("jump", "Space"), ("jump", "pad:A")]).unwrap();
+    map.rebind("jump", Binding::Button(Input::Key(Scancode::W)));
+    assert_eq!(
+        map.bindings("jump").collect::<base_64::mem::Vec<_>>()[..],
+        [Binding::Button(Input::Key(Scancode::W))]
+    );
+
+    let mut input = InputState::new();
+    frame(&mut input, &[key(Scancode::Space, true)]);
+    assert!(!map.held(&input, "jump"));
+    frame(&mut input, &[key(Scancode::W, true)]);
+    assert!(map.pressed(&input, "jump"));
+
+    map.unbind("jump");
+    assert_eq!(map.bindings("jump").count(), 0);
+}
+
+#[test]
+fn axis_deadzone_and_clamp() {
+    let map = ActionMap::from_table(&[("move", "pad:LeftX"), ("move", "keys:A,D")]).unwrap();
+    let mut input = InputState::new();
+
+    frame(&mut input, &[stick(i16::MAX / 10)]);
+    assert_eq!(map.axis(&input, "move"), 0.0);
+
+    frame(&mut input, &[stick(i16::MIN)]);
+    assert_eq!(map.axis(&input, "move"), -1.0);
+
+    // Stick and keys add up, but never past full deflection
+    frame(&mut input, &[stick(i16::MAX / 2), key(Scancode::D, true)]);
+    assert_eq!(map.axis(&input, "move"), 1.0);
+
+    frame(&mut input, &[stick(0), key(Scancode::A, true)]);
+    assert_eq!(map.axis(&input, "move"), 0.0);
+}
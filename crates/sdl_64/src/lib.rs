@@ -1,5 +1,11 @@
 #![no_std]
+pub mod audio;
+pub mod error;
 pub mod event;
+pub mod input;
+pub mod replay;
+pub mod time;
 pub mod window;
 
+pub use error::{Error, Message};
 pub use sdl2_sys::SDL_GL_GetProcAddress as load_gl;
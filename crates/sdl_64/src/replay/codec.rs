@@ -0,0 +1,496 @@
+use crate::event::{Axis, Button, Event, Keycode, Modifiers, MouseButton, Scancode, WindowEvent};
+use base_64::mem::Vec;
+
+const QUIT: u8 = 0;
+const KEYBOARD: u8 = 1;
+const TEXT_INPUT: u8 = 2;
+const MOUSE_MOTION: u8 = 3;
+const MOUSE_BUTTON: u8 = 4;
+const MOUSE_WHEEL: u8 = 5;
+const WINDOW: u8 = 6;
+const CONTROLLER_AXIS: u8 = 7;
+const CONTROLLER_BUTTON: u8 = 8;
+const CONTROLLER_ADDED: u8 = 9;
+const CONTROLLER_REMOVED: u8 = 10;
+const CONTROLLER_REMAPPED: u8 = 11;
+const OTHER: u8 = 12;
+
+pub(crate) struct Writer<'a>(pub(crate) &'a mut Vec<u8>);
+
+impl Writer<'_> {
+    pub(crate) fn u8(&mut self, value: u8) {
+        self.0.push(value);
+    }
+
+    // LEB128
+    pub(crate) fn uvar(&mut self, mut value: u32) {
+        while value >= 0x80 {
+            self.0.push(value as u8 | 0x80);
+            value >>= 7;
+        }
+        self.0.push(value as u8);
+    }
+
+    // Zigzag, so small negative values stay short
+    pub(crate) fn ivar(&mut self, value: i32) {
+        self.uvar(((value << 1) ^ (value >> 31)) as u32);
+    }
+
+    fn pair(&mut self, [x, y]: [i32; 2]) {
+        self.ivar(x);
+        self.ivar(y);
+    }
+
+    pub(crate) fn event(&mut self, event: &Event) {
+        match *event {
+            Event::Quit => self.u8(QUIT),
+            Event::Keyboard {
+                down,
+                repeat,
+                timestamp,
+                key,
+                scancode,
+                modifiers,
+            } => {
+                self.u8(KEYBOARD);
+                self.u8(down as u8 | (repeat as u8) << 1);
+                self.uvar(timestamp);
+                self.ivar(key.to_sdl());
+                self.ivar(scancode.to_sdl());
+                self.uvar(modifiers.to_sdl() as u32);
+            }
+            Event::TextInput { text } => {
+                self.u8(TEXT_INPUT);
+                let len = text.iter().position(|&c| c == 0).unwrap_or(text.len());
+                self.u8(len as u8);
+                self.0.extend(text[..len].iter().map(|&c| c as u8));
+            }
+            Event::MouseMotion {
+                timestamp,
+                window,
+                pos,
+                rel,
+                buttons,
+            } => {
+                self.u8(MOUSE_MOTION);
+                self.uvar(timestamp);
+                self.uvar(window);
+                self.pair(pos);
+                self.pair(rel);
+                self.uvar(buttons);
+            }
+            Event::MouseButton {
+                down,
+                timestamp,
+                window,
+                button,
+                clicks,
+                pos,
+            } => {
+                self.u8(MOUSE_BUTTON);
+                self.u8(down as u8);
+                self.uvar(timestamp);
+                self.uvar(window);
+                self.u8(button.to_sdl());
+                self.u8(clicks);
+                self.pair(pos);
+            }
+            Event::MouseWheel {
+                timestamp,
+                window,
+                delta,
+            } => {
+                self.u8(MOUSE_WHEEL);
+                self.uvar(timestamp);
+                self.uvar(window);
+                self.pair(delta);
+            }
+            Event::Window {
+                timestamp,
+                window,
+                event,
+            } => {
+                let (event, data) = event.to_sdl();
+                self.u8(WINDOW);
+                self.uvar(timestamp);
+                self.uvar(window);
+                self.u8(event);
+                self.pair(data);
+            }
+            Event::ControllerAxis {
+                timestamp,
+                which,
+                axis,
+                value,
+            } => {
+                self.u8(CONTROLLER_AXIS);
+                self.uvar(timestamp);
+                self.ivar(which);
+                self.u8(axis.to_sdl());
+                self.ivar(value as i32);
+            }
+            Event::ControllerButton {
+                down,
+                timestamp,
+                which,
+                button,
+            } => {
+                self.u8(CONTROLLER_BUTTON);
+                self.u8(down as u8);
+                self.uvar(timestamp);
+                self.ivar(which);
+                self.u8(button.to_sdl());
+            }
+            Event::ControllerAdded { timestamp, which } => {
+                self.u8(CONTROLLER_ADDED);
+                self.uvar(timestamp);
+                self.ivar(which);
+            }
+            Event::ControllerRemoved { timestamp, which } => {
+                self.u8(CONTROLLER_REMOVED);
+                self.uvar(timestamp);
+                self.ivar(which);
+            }
+            Event::ControllerRemapped { timestamp, which } => {
+                self.u8(CONTROLLER_REMAPPED);
+                self.uvar(timestamp);
+                self.ivar(which);
+            }
+            Event::Other { timestamp, type_ } => {
+                self.u8(OTHER);
+                self.uvar(timestamp);
+                self.uvar(type_);
+            }
+        }
+    }
+}
+
+pub(crate) struct Reader<'a> {
+    pub(crate) data: &'a [u8],
+    pub(crate) pos: usize,
+}
+
+impl Reader<'_> {
+    pub(crate) fn is_empty(&self) -> bool {
+        self.pos >= self.data.len()
+    }
+
+    pub(crate) fn u8(&mut self) -> Option<u8> {
+        let value = *self.data.get(self.pos)?;
+        self.pos += 1;
+        Some(value)
+    }
+
+    pub(crate) fn uvar(&mut self) -> Option<u32> {
+        let mut value = 0;
+        for shift in (0..35).step_by(7) {
+            let byte = self.u8()?;
+            value |= ((byte & 0x7F) as u32) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+
+        None
+    }
+
+    pub(crate) fn ivar(&mut self) -> Option<i32> {
+        let value = self.uvar()?;
+        Some((value >> 1) as i32 ^ -((value & 1) as i32))
+    }
+
+    fn pair(&mut self) -> Option<[i32; 2]> {
+        Some([self.ivar()?, self.ivar()?])
+    }
+
+    pub(crate) fn event(&mut self) -> Option<Event> {
+        Some(match self.u8()? {
+            QUIT => Event::Quit,
+            KEYBOARD => {
+                let flags = self.u8()?;
+                Event::Keyboard {
+                    down: flags & 1 != 0,
+                    repeat: flags & 2 != 0,
+                    timestamp: self.uvar()?,
+                    key: Keycode::from_sdl(self.ivar()?),
+                    scancode: Scancode::from_sdl(self.ivar()?),
+                    modifiers: Modifiers::from_sdl(self.uvar()? as u16),
+                }
+            }
+            TEXT_INPUT => {
+                let len = self.u8()? as usize;
+                let bytes = self.data.get(self.pos..self.pos + len)?;
+                self.pos += len;
+
+                let mut text = [0; 32];
+                for (c, &byte) in text.iter_mut().zip(bytes) {
+                    *c = byte as i8;
+                }
+                Event::TextInput { text }
+            }
+            MOUSE_MOTION => Event::MouseMotion {
+                timestamp: self.uvar()?,
+                window: self.uvar()?,
+                pos: self.pair()?,
+                rel: self.pair()?,
+                buttons: self.uvar()?,
+            },
+            MOUSE_BUTTON => Event::MouseButton {
+                down: self.u8()? != 0,
+                timestamp: self.uvar()?,
+                window: self.uvar()?,
+                button: MouseButton::from_sdl(self.u8()?),
+                clicks: self.u8()?,
+                pos: self.pair()?,
+            },
+            MOUSE_WHEEL => Event::MouseWheel {
+                timestamp: self.uvar()?,
+                window: self.uvar()?,
+                delta: self.pair()?,
+            },
+            WINDOW => {
+                let timestamp = self.uvar()?;
+                let window = self.uvar()?;
+                let event = WindowEvent::from_raw(self.u8()?, self.pair()?);
+                Event::Window {
+                    timestamp,
+                    window,
+                    event,
+                }
+            }
+            CONTROLLER_AXIS => Event::ControllerAxis {
+                timestamp: self.uvar()?,
+                which: self.ivar()?,
+                axis: Axis::from_sdl(self.u8()?),
+                value: self.ivar()? as i16,
+            },
+            CONTROLLER_BUTTON => Event::ControllerButton {
+                down: self.u8()? != 0,
+                timestamp: self.uvar()?,
+                which: self.ivar()?,
+                button: Button::from_sdl(self.u8()?),
+            },
+            CONTROLLER_ADDED => Event::ControllerAdded {
+                timestamp: self.uvar()?,
+                which: self.ivar()?,
+            },
+            CONTROLLER_REMOVED => Event::ControllerRemoved {
+                timestamp: self.uvar()?,
+                which: self.ivar()?,
+            },
+            CONTROLLER_REMAPPED => Event::ControllerRemapped {
+                timestamp: self.uvar()?,
+                which: self.ivar()?,
+            },
+            OTHER => Event::Other {
+                timestamp: self.uvar()?,
+                type_: self.uvar()?,
+            },
+            tag => {
+                log::error!("corrupt replay, unknown event tag {}", tag);
+                return None;
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reader, Writer};
+    use crate::event::{
+        Axis, Button, Event, Keycode, Modifiers, MouseButton, Scancode, WindowEvent,
+    };
+    use crate::replay::{Replay, MAGIC, VERSION};
+    use base_64::mem::Vec;
+
+    fn events() -> Vec<Event> {
+        let mut text = [0; 32];
+        for (c, &byte) in text.iter_mut().zip("h\u{e9}llo".as_bytes()) {
+            *c = byte as i8;
+        }
+
+        [
+            Event::Quit,
+            Event::Keyboard {
+                down: true,
+                repeat: true,
+                timestamp: u32::MAX,
+                key: Keycode::Space,
+                scancode: Scancode::Unknown(i32::MIN),
+                modifiers: Modifiers::LSHIFT | Modifiers::RGUI,
+            },
+            Event::Keyboard {
+                down: false,
+                repeat: false,
+                timestamp: 0,
+                key: Keycode::Unknown(i32::MAX),
+                scancode: Scancode::Escape,
+                modifiers: Modifiers::NONE,
+            },
+            Event::TextInput { text },
+            Event::TextInput {
+                text: [b'x' as i8; 32],
+            },
+            Event::MouseMotion {
+                timestamp: 1,
+                window: u32::MAX,
+                pos: [i32::MIN, i32::MAX],
+                rel: [-1, 0],
+                buttons: u32::MAX,
+            },
+            Event::MouseButton {
+                down: true,
+                timestamp: 2,
+                window: 1,
+                button: MouseButton::Other(200),
+                clicks: 2,
+                pos: [-5, 7],
+            },
+            Event::MouseWheel {
+                timestamp: 3,
+                window: 0,
+                delta: [0, i32::MIN],
+            },
+            Event::Window {
+                timestamp: 4,
+                window: 2,
+                event: WindowEvent::Moved {
+                    pos: [i32::MIN, -1],
+                },
+            },
+            Event::Window {
+                timestamp: 5,
+                window: 2,
+                event: WindowEvent::Close,
+            },
+            Event::ControllerAxis {
+                timestamp: 6,
+                which: i32::MIN,
+                axis: Axis::LeftX,
+                value: i16::MIN,
+            },
+            Event::ControllerButton {
+                down: false,
+                timestamp: 7,
+                which: i32::MAX,
+                button: Button::Y,
+            },
+            Event::ControllerAdded {
+                timestamp: 8,
+                which: 0,
+            },
+            Event::ControllerRemoved {
+                timestamp: 9,
+                which: -1,
+            },
+            Event::ControllerRemapped {
+                timestamp: 10,
+                which: 3,
+            },
+            Event::Other {
+                timestamp: u32::MAX,
+                type_: u32::MAX,
+            },
+        ]
+        .into_iter()
+        .collect()
+    }
+
+    fn encode(event: &Event) -> Vec<u8> {
+        let mut data = Vec::new();
+        Writer(&mut data).event(event);
+        data
+    }
+
+    #[test]
+    fn varints() {
+        let values = [0, 1, 127, 128, 16383, 16384, u32::MAX - 1, u32::MAX];
+        let signed = [0, -1, 1, -64, 64, i32::MIN, i32::MAX];
+
+        let mut data = Vec::new();
+        let mut writer = Writer(&mut data);
+        values.iter().for_each(|&value| writer.uvar(value));
+        signed.iter().for_each(|&value| writer.ivar(value));
+
+        let mut reader = Reader {
+            data: &data,
+            pos: 0,
+        };
+        for &value in values.iter() {
+            assert_eq!(reader.uvar(), Some(value));
+        }
+        for &value in signed.iter() {
+            assert_eq!(reader.ivar(), Some(value));
+        }
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn small_values_stay_short() {
+        assert_eq!(
+            &encode(&Event::ControllerAdded {
+                timestamp: 1,
+                which: -1
+            })[..],
+            &[9, 1, 1]
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let events = events();
+        let mut data = Vec::new();
+        let mut writer = Writer(&mut data);
+        events.iter().for_each(|event| writer.event(event));
+
+        let mut reader = Reader {
+            data: &data,
+            pos: 0,
+        };
+        for event in events.iter() {
+            assert_eq!(reader.event().as_ref(), Some(event));
+        }
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn truncated() {
+        for event in events().iter() {
+            let data = encode(event);
+            for len in 0..data.len() {
+                let mut reader = Reader {
+                    data: &data[..len],
+                    pos: 0,
+                };
+                assert_eq!(reader.event(), None, "{:?} cut to {} bytes", event, len);
+            }
+        }
+    }
+
+    #[test]
+    fn frame_overflow_ends_replay() {
+        let mut data = Vec::new();
+        data.extend_from_slice(MAGIC);
+        data.push(VERSION);
+        let mut writer = Writer(&mut data);
+        writer.uvar(u32::MAX);
+        writer.event(&Event::Quit);
+        writer.uvar(1);
+        writer.event(&Event::Quit);
+
+        let mut replay = Replay::new(&data).unwrap();
+        replay.frame = u32::MAX;
+        assert_eq!(replay.next(), Some(Event::Quit));
+        assert_eq!(replay.next(), None);
+        assert!(replay.is_finished());
+    }
+
+    #[test]
+    fn unknown_tag() {
+        let mut reader = Reader {
+            data: &[0xff],
+            pos: 0,
+        };
+        assert_eq!(reader.event(), None);
+    }
+}
@@ -0,0 +1,192 @@
+mod codec;
+
+use crate::{event::Event, Error};
+use base_64::mem::Vec;
+use codec::{Reader, Writer};
+use sdl2_sys::{SDL_PushEvent, SDL_VideoInit, SDL_VideoQuit};
+
+const MAGIC: &[u8; 4] = b"_64R";
+const VERSION: u8 = 1;
+
+// Log layout: magic, version, then per event the frames elapsed since the previous event
+// followed by the event itself, all integers as varints
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    data: Vec<u8>,
+    frame: u32,
+    last_frame: u32,
+}
+
+impl Recorder {
+    pub fn new() -> Self {
+        let mut data = Vec::with_capacity(256);
+        data.extend_from_slice(MAGIC);
+        data.push(VERSION);
+
+        Self {
+            data,
+            frame: 0,
+            last_frame: 0,
+        }
+    }
+
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    pub fn next_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    pub fn record(&mut self, event: &Event) {
+        let mut writer = Writer(&mut self.data);
+        writer.uvar(self.frame - self.last_frame);
+        writer.event(event);
+        self.last_frame = self.frame;
+    }
+
+    /// Records every event pulled through the returned iterator
+    pub fn tap<I: Iterator<Item = Event>>(&mut self, events: I) -> Tap<'_, I> {
+        Tap {
+            recorder: self,
+            events,
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.data
+    }
+}
+
+impl Default for Recorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub struct Tap<'a, I> {
+    recorder: &'a mut Recorder,
+    events: I,
+}
+
+impl<I: Iterator<Item = Event>> Iterator for Tap<'_, I> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        let event = self.events.next()?;
+        self.recorder.record(&event);
+        Some(event)
+    }
+}
+
+/// Plays a log back a frame at a time; iterating yields the current frame's events and then
+/// `None`, the same as draining an `EventFeed`
+pub struct Replay<'a> {
+    reader: Reader<'a>,
+    frame: u32,
+    next: Option<(u32, Event)>,
+}
+
+impl<'a> Replay<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, Error> {
+        if data.len() < MAGIC.len() + 1 || &data[..MAGIC.len()] != MAGIC {
+            log::error!("not an input recording");
+            return Err(Error::NotARecording);
+        }
+        if data[MAGIC.len()] != VERSION {
+            log::error!("unsupported recording version {}", data[MAGIC.len()]);
+            return Err(Error::RecordingVersion(data[MAGIC.len()]));
+        }
+
+        let mut replay = Self {
+            reader: Reader {
+                data,
+                pos: MAGIC.len() + 1,
+            },
+            frame: 0,
+            next: None,
+        };
+        replay.next = replay.read(0);
+        Ok(replay)
+    }
+
+    fn read(&mut self, last_frame: u32) -> Option<(u32, Event)> {
+        if self.reader.is_empty() {
+            return None;
+        }
+
+        // A delta that overflows can only come from a corrupt log, which ends like a cut off one
+        let frame = last_frame.checked_add(self.reader.uvar()?)?;
+        Some((frame, self.reader.event()?))
+    }
+
+    pub fn frame(&self) -> u32 {
+        self.frame
+    }
+
+    pub fn next_frame(&mut self) {
+        self.frame += 1;
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.next.is_none()
+    }
+
+    /// Pushes the current frame's events onto SDL's queue, for an `EventFeed` to pick up as if
+    /// they were real. SDL restamps pushed events, so iterate instead when timestamps matter
+    pub fn inject(&mut self) -> usize {
+        let mut pushed = 0;
+        for event in self.by_ref() {
+            match event.to_sdl() {
+                Some(mut raw) => unsafe {
+                    if SDL_PushEvent(&mut raw) == 1 {
+                        pushed += 1;
+                    } else {
+                        log::warn!("failed to push replayed {:?}", event);
+                    }
+                },
+                None => log::debug!("can't inject {:?}, skipping", event),
+            }
+        }
+
+        pushed
+    }
+}
+
+impl Iterator for Replay<'_> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        match self.next {
+            Some((frame, event)) if frame <= self.frame => {
+                self.next = self.read(frame);
+                Some(event)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// SDL's dummy video driver, enough for the event queue and injection without a display
+pub struct DummyVideo;
+
+impl DummyVideo {
+    pub fn new() -> Result<Self, Error> {
+        unsafe {
+            if SDL_VideoInit(c"dummy".as_ptr()) != 0 {
+                log::error!("dummy video driver unavailable");
+                return Err(Error::sdl());
+            }
+        }
+
+        Ok(Self)
+    }
+}
+
+impl Drop for DummyVideo {
+    fn drop(&mut self) {
+        unsafe {
+            SDL_VideoQuit();
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use sdl2_sys::{SDL_Delay, SDL_GetPerformanceCounter, SDL_GetPerformanceFrequency};
+
+#[derive(Debug, Clone, Copy)]
+pub struct Clock {
+    freq: f64,
+    start: u64,
+    last: u64,
+}
+
+impl Clock {
+    pub fn new() -> Self {
+        let start = unsafe { SDL_GetPerformanceCounter() };
+        Self {
+            freq: unsafe { SDL_GetPerformanceFrequency() } as f64,
+            start,
+            last: start,
+        }
+    }
+
+    /// Seconds since the clock was created
+    pub fn now(&self) -> f64 {
+        let counter = unsafe { SDL_GetPerformanceCounter() };
+        counter.wrapping_sub(self.start) as f64 / self.freq
+    }
+
+    /// Seconds since the previous tick, or since creation for the first one
+    pub fn tick(&mut self) -> f64 {
+        let counter = unsafe { SDL_GetPerformanceCounter() };
+        let dt = counter.wrapping_sub(self.last) as f64 / self.freq;
+        self.last = counter;
+        dt
+    }
+
+    /// Blocks until `now()` reaches `until`; sleeps while it can, then spins for precision
+    pub fn wait_until(&self, until: f64) {
+        // SDL_Delay routinely oversleeps by a millisecond or two
+        const SPIN: f64 = 0.002;
+
+        loop {
+            let left = until - self.now();
+            if left <= 0.0 {
+                break;
+            } else if left > SPIN {
+                unsafe {
+                    SDL_Delay(((left - SPIN) * 1000.0) as u32);
+                }
+            } else {
+                core::hint::spin_loop();
+            }
+        }
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
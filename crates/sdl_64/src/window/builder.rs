@@ -0,0 +1,122 @@
+use super::Window;
+use crate::Error;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Position {
+    Undefined,
+    Centered,
+    At([i32; 2]),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fullscreen {
+    Off,
+    // Changes the display mode to the window size
+    Exclusive,
+    // Covers the display at its current mode
+    Desktop,
+    // Windowed, without decorations
+    Borderless,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SwapInterval {
+    Immediate,
+    VSync,
+    // Late frames swap immediately instead of waiting a whole refresh; falls back to VSync
+    Adaptive,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct WindowBuilder<'a> {
+    pub(crate) name: &'a [u8],
+    pub(crate) dim: [i32; 2],
+    pub(crate) position: Position,
+    pub(crate) fullscreen: Fullscreen,
+    pub(crate) resizable: bool,
+    pub(crate) hidden: bool,
+    pub(crate) headless: bool,
+    pub(crate) swap_interval: SwapInterval,
+    pub(crate) samples: u8,
+    pub(crate) depth_bits: u8,
+    pub(crate) stencil_bits: u8,
+    pub(crate) debug: bool,
+    pub(crate) srgb: bool,
+}
+
+impl<'a> WindowBuilder<'a> {
+    /// `name` must be nul terminated
+    pub fn new(name: &'a [u8], w: i32, h: i32) -> Self {
+        Self {
+            name,
+            dim: [w, h],
+            position: Position::Undefined,
+            fullscreen: Fullscreen::Off,
+            resizable: false,
+            hidden: false,
+            headless: false,
+            swap_interval: SwapInterval::Immediate,
+            samples: 0,
+            depth_bits: 24,
+            stencil_bits: 8,
+            debug: cfg!(debug_assertions),
+            srgb: false,
+        }
+    }
+
+    pub fn position(self, position: Position) -> Self {
+        Self { position, ..self }
+    }
+
+    pub fn fullscreen(self, fullscreen: Fullscreen) -> Self {
+        Self { fullscreen, ..self }
+    }
+
+    pub fn resizable(self, resizable: bool) -> Self {
+        Self { resizable, ..self }
+    }
+
+    pub fn hidden(self, hidden: bool) -> Self {
+        Self { hidden, ..self }
+    }
+
+    /// Renders through SDL's offscreen driver, for tests and CI without a display
+    pub fn headless(self, headless: bool) -> Self {
+        Self { headless, ..self }
+    }
+
+    pub fn swap_interval(self, swap_interval: SwapInterval) -> Self {
+        Self {
+            swap_interval,
+            ..self
+        }
+    }
+
+    /// MSAA samples per pixel, 0 to disable
+    pub fn samples(self, samples: u8) -> Self {
+        Self { samples, ..self }
+    }
+
+    pub fn depth_bits(self, depth_bits: u8) -> Self {
+        Self { depth_bits, ..self }
+    }
+
+    pub fn stencil_bits(self, stencil_bits: u8) -> Self {
+        Self {
+            stencil_bits,
+            ..self
+        }
+    }
+
+    pub fn debug(self, debug: bool) -> Self {
+        Self { debug, ..self }
+    }
+
+    pub fn srgb(self, srgb: bool) -> Self {
+        Self { srgb, ..self }
+    }
+
+    pub fn build(self) -> Result<Window, Error> {
+        Window::open(&self)
+    }
+}
@@ -1,49 +1,157 @@
+mod builder;
+
+use crate::Error;
+pub use builder::{Fullscreen, Position, SwapInterval, WindowBuilder};
 use sdl2_sys::*;
 
 #[derive(Debug)]
 pub struct Window {
     window: *mut SDL_Window,
     _gl: SDL_GLContext,
+    headless: bool,
+    srgb: bool,
 }
 
 impl Window {
-    pub fn new(name: &[u8], w: i32, h: i32) -> Result<Self, ()> {
+    pub fn new(name: &[u8], w: i32, h: i32) -> Result<Self, Error> {
+        WindowBuilder::new(name, w, h).build()
+    }
+
+    pub fn headless(name: &[u8], w: i32, h: i32) -> Result<Self, Error> {
+        WindowBuilder::new(name, w, h).headless(true).build()
+    }
+
+    pub fn builder(name: &[u8], w: i32, h: i32) -> WindowBuilder<'_> {
+        WindowBuilder::new(name, w, h)
+    }
+
+    pub(crate) fn open(config: &WindowBuilder) -> Result<Self, Error> {
+        let headless = config.headless;
         unsafe {
-            SDL_InitSubSystem(SDL_INIT_VIDEO);
-
-            let window = SDL_CreateWindow(
-                name.as_ptr() as *const i8,
-                SDL_WINDOWPOS_UNDEFINED_MASK as _,
-                SDL_WINDOWPOS_UNDEFINED_MASK as _,
-                w,
-                h,
-                SDL_WindowFlags::SDL_WINDOW_OPENGL as u32
-                    | SDL_WindowFlags::SDL_WINDOW_SHOWN as u32,
-            );
+            if headless {
+                if SDL_VideoInit(c"offscreen".as_ptr()) != 0 {
+                    log::error!("offscreen video driver unavailable");
+                    return Err(Error::sdl());
+                }
+            } else {
+                SDL_InitSubSystem(SDL_INIT_VIDEO);
+            }
 
-            if !window.is_null() {
-                SDL_GL_SetAttribute(
-                    SDL_GLattr::SDL_GL_CONTEXT_PROFILE_MASK,
-                    SDL_GLprofile::SDL_GL_CONTEXT_PROFILE_CORE as i32,
-                );
+            // Context attributes only apply to windows created after they are set
+            SDL_GL_ResetAttributes();
+            SDL_GL_SetAttribute(
+                SDL_GLattr::SDL_GL_CONTEXT_PROFILE_MASK,
+                SDL_GLprofile::SDL_GL_CONTEXT_PROFILE_CORE as i32,
+            );
+            if config.debug {
                 SDL_GL_SetAttribute(
                     SDL_GLattr::SDL_GL_CONTEXT_FLAGS,
                     SDL_GLcontextFlag::SDL_GL_CONTEXT_DEBUG_FLAG as _,
                 );
-                SDL_GL_SetAttribute(SDL_GLattr::SDL_GL_CONTEXT_MAJOR_VERSION, 4);
-                SDL_GL_SetAttribute(SDL_GLattr::SDL_GL_CONTEXT_MINOR_VERSION, 6);
+            }
+            SDL_GL_SetAttribute(SDL_GLattr::SDL_GL_CONTEXT_MAJOR_VERSION, 4);
+            SDL_GL_SetAttribute(SDL_GLattr::SDL_GL_CONTEXT_MINOR_VERSION, 6);
+            SDL_GL_SetAttribute(SDL_GLattr::SDL_GL_DOUBLEBUFFER, 1);
+            SDL_GL_SetAttribute(SDL_GLattr::SDL_GL_DEPTH_SIZE, config.depth_bits as _);
+            SDL_GL_SetAttribute(SDL_GLattr::SDL_GL_STENCIL_SIZE, config.stencil_bits as _);
+            if config.samples > 0 {
+                SDL_GL_SetAttribute(SDL_GLattr::SDL_GL_MULTISAMPLEBUFFERS, 1);
+                SDL_GL_SetAttribute(SDL_GLattr::SDL_GL_MULTISAMPLESAMPLES, config.samples as _);
+            }
+            if config.srgb {
+                SDL_GL_SetAttribute(SDL_GLattr::SDL_GL_FRAMEBUFFER_SRGB_CAPABLE, 1);
+            }
 
-                let _gl = SDL_GL_CreateContext(window);
-                if SDL_GL_MakeCurrent(window, _gl) != 0 {
-                    panic!();
-                }
+            let [x, y] = match config.position {
+                Position::Undefined => [SDL_WINDOWPOS_UNDEFINED_MASK as i32; 2],
+                Position::Centered => [SDL_WINDOWPOS_CENTERED_MASK as i32; 2],
+                Position::At(pos) => pos,
+            };
 
-                Ok(Self { window, _gl })
+            let mut flags = SDL_WindowFlags::SDL_WINDOW_OPENGL as u32;
+            flags |= match config.fullscreen {
+                Fullscreen::Off => 0,
+                Fullscreen::Exclusive => SDL_WindowFlags::SDL_WINDOW_FULLSCREEN as u32,
+                Fullscreen::Desktop => SDL_WindowFlags::SDL_WINDOW_FULLSCREEN_DESKTOP as u32,
+                Fullscreen::Borderless => SDL_WindowFlags::SDL_WINDOW_BORDERLESS as u32,
+            };
+            if config.resizable {
+                flags |= SDL_WindowFlags::SDL_WINDOW_RESIZABLE as u32;
+            }
+            flags |= if config.hidden || headless {
+                SDL_WindowFlags::SDL_WINDOW_HIDDEN as u32
             } else {
-                SDL_QuitSubSystem(SDL_INIT_VIDEO);
-                Err(())
+                SDL_WindowFlags::SDL_WINDOW_SHOWN as u32
+            };
+
+            let [w, h] = config.dim;
+            let window = SDL_CreateWindow(config.name.as_ptr() as *const i8, x, y, w, h, flags);
+            if window.is_null() {
+                log::error!("failed to create window");
+                let err = Error::sdl();
+                Self::quit_video(headless);
+                return Err(err);
+            }
+
+            let _gl = SDL_GL_CreateContext(window);
+            if _gl.is_null() || SDL_GL_MakeCurrent(window, _gl) != 0 {
+                log::error!("failed to create GL context");
+                let err = Error::sdl();
+                if !_gl.is_null() {
+                    SDL_GL_DeleteContext(_gl);
+                }
+                SDL_DestroyWindow(window);
+                Self::quit_video(headless);
+                return Err(err);
             }
+
+            let interval = match config.swap_interval {
+                SwapInterval::Immediate => 0,
+                SwapInterval::VSync => 1,
+                SwapInterval::Adaptive => -1,
+            };
+            if SDL_GL_SetSwapInterval(interval) != 0 {
+                log::warn!("swap interval {:?} unsupported", config.swap_interval);
+                if config.swap_interval == SwapInterval::Adaptive {
+                    SDL_GL_SetSwapInterval(1);
+                }
+            }
+
+            Ok(Self {
+                window,
+                _gl,
+                headless,
+                srgb: config.srgb,
+            })
+        }
+    }
+
+    /// In screen points, which differ from pixels on HiDPI displays
+    pub fn size(&self) -> [i32; 2] {
+        let [mut w, mut h] = [0, 0];
+        unsafe {
+            SDL_GetWindowSize(self.window, &mut w, &mut h);
+        }
+
+        [w, h]
+    }
+
+    /// In pixels, the size of the default framebuffer
+    pub fn drawable_size(&self) -> [i32; 2] {
+        let [mut w, mut h] = [0, 0];
+        unsafe {
+            SDL_GL_GetDrawableSize(self.window, &mut w, &mut h);
         }
+
+        [w, h]
+    }
+
+    pub fn is_headless(&self) -> bool {
+        self.headless
+    }
+
+    pub fn is_srgb(&self) -> bool {
+        self.srgb
     }
 
     pub fn swap(&self) {
@@ -51,6 +159,16 @@ impl Window {
             SDL_GL_SwapWindow(self.window);
         }
     }
+
+    fn quit_video(headless: bool) {
+        unsafe {
+            if headless {
+                SDL_VideoQuit();
+            } else {
+                SDL_QuitSubSystem(SDL_INIT_VIDEO);
+            }
+        }
+    }
 }
 
 impl Drop for Window {
@@ -58,7 +176,8 @@ impl Drop for Window {
         unsafe {
             log::info!("dropping window");
             SDL_GL_DeleteContext(self._gl);
-            SDL_QuitSubSystem(SDL_INIT_VIDEO);
+            SDL_DestroyWindow(self.window);
         }
+        Self::quit_video(self.headless);
     }
 }
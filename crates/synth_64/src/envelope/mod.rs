@@ -0,0 +1,101 @@
+/// Attack, decay and release in seconds, sustain as a level
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Envelope {
+    pub attack: f32,
+    pub decay: f32,
+    pub sustain: f32,
+    pub release: f32,
+}
+
+impl Envelope {
+    pub const fn new(attack: f32, decay: f32, sustain: f32, release: f32) -> Self {
+        Self {
+            attack,
+            decay,
+            sustain,
+            release,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Stage {
+    Idle,
+    Attack,
+    Decay,
+    Sustain,
+    Release { step: f32 },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct EnvelopeState {
+    stage: Stage,
+    level: f32,
+}
+
+impl EnvelopeState {
+    pub fn new() -> Self {
+        Self {
+            stage: Stage::Idle,
+            level: 0.0,
+        }
+    }
+
+    /// Restarts the attack from the current level, so retriggers don't click
+    pub fn gate_on(&mut self) {
+        self.stage = Stage::Attack;
+    }
+
+    pub fn gate_off(&mut self, envelope: &Envelope, rate: f32) {
+        if self.stage != Stage::Idle {
+            self.stage = Stage::Release {
+                step: self.level / frames(envelope.release, rate),
+            };
+        }
+    }
+
+    pub fn is_idle(&self) -> bool {
+        self.stage == Stage::Idle
+    }
+
+    pub fn next(&mut self, envelope: &Envelope, rate: f32) -> f32 {
+        match self.stage {
+            Stage::Idle => {}
+            Stage::Attack => {
+                self.level += 1.0 / frames(envelope.attack, rate);
+                if self.level >= 1.0 {
+                    self.level = 1.0;
+                    self.stage = Stage::Decay;
+                }
+            }
+            Stage::Decay => {
+                self.level -= (1.0 - envelope.sustain) / frames(envelope.decay, rate);
+                if self.level <= envelope.sustain {
+                    self.level = envelope.sustain;
+                    self.stage = Stage::Sustain;
+                }
+            }
+            Stage::Sustain => self.level = envelope.sustain,
+            Stage::Release { step } => {
+                self.level -= step;
+                if self.level <= 0.0 {
+                    self.level = 0.0;
+                    self.stage = Stage::Idle;
+                }
+            }
+        }
+
+        self.level
+    }
+}
+
+impl Default for EnvelopeState {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// At least one, so zero-length segments jump instead of dividing by zero
+fn frames(seconds: f32, rate: f32) -> f32 {
+    (seconds * rate).max(1.0)
+}
@@ -0,0 +1,56 @@
+use crate::sine;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FilterMode {
+    LowPass,
+    HighPass,
+    BandPass,
+}
+
+/// `cutoff` in Hz, `resonance` from 0 (none) to just under 1 (self-oscillating)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Filter {
+    pub mode: FilterMode,
+    pub cutoff: f32,
+    pub resonance: f32,
+}
+
+impl Filter {
+    pub const fn new(mode: FilterMode, cutoff: f32, resonance: f32) -> Self {
+        Self {
+            mode,
+            cutoff,
+            resonance,
+        }
+    }
+}
+
+// Chamberlin state variable filter, all three responses from the same two integrators
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FilterState {
+    low: f32,
+    band: f32,
+}
+
+impl FilterState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn next(&mut self, filter: &Filter, input: f32, rate: f32) -> f32 {
+        // Only stable up to about a sixth of the sample rate
+        let cutoff = filter.cutoff.min(rate / 6.0);
+        let f = 2.0 * sine(0.5 * cutoff / rate);
+        let damp = 2.0 * (1.0 - filter.resonance.clamp(0.0, 0.98));
+
+        self.low += f * self.band;
+        let high = input - self.low - damp * self.band;
+        self.band += f * high;
+
+        match filter.mode {
+            FilterMode::LowPass => self.low,
+            FilterMode::HighPass => high,
+            FilterMode::BandPass => self.band,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use base_64::mem::Vec;
+
+/// Feedback echo; `time` in seconds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Delay {
+    pub time: f32,
+    pub feedback: f32,
+    pub mix: f32,
+}
+
+impl Delay {
+    pub const fn new(time: f32, feedback: f32, mix: f32) -> Self {
+        Self {
+            time,
+            feedback,
+            mix,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Line {
+    buf: Vec<f32>,
+    pos: usize,
+}
+
+impl Line {
+    fn new(len: usize) -> Self {
+        Self {
+            buf: (0..len.max(1)).map(|_| 0.0).collect(),
+            pos: 0,
+        }
+    }
+
+    fn read(&self) -> f32 {
+        self.buf[self.pos]
+    }
+
+    fn write(&mut self, value: f32) {
+        self.buf[self.pos] = value;
+        self.pos = (self.pos + 1) % self.buf.len();
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DelayState {
+    line: Line,
+}
+
+impl DelayState {
+    pub fn new(delay: &Delay, rate: f32) -> Self {
+        Self {
+            line: Line::new((delay.time * rate) as usize),
+        }
+    }
+
+    pub fn next(&mut self, delay: &Delay, input: f32) -> f32 {
+        let echo = self.line.read();
+        self.line.write(input + echo * delay.feedback);
+        input + echo * delay.mix
+    }
+}
+
+/// `size` sets the tail length and `damping` how fast the highs die out, both 0..1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reverb {
+    pub size: f32,
+    pub damping: f32,
+    pub mix: f32,
+}
+
+impl Reverb {
+    pub const fn new(size: f32, damping: f32, mix: f32) -> Self {
+        Self { size, damping, mix }
+    }
+}
+
+// Freeverb's tunings at 44.1kHz. Not coprime, just spread unevenly so the combs' echoes
+// rarely coincide
+const COMBS: [usize; 4] = [1116, 1188, 1277, 1356];
+const ALLPASSES: [usize; 2] = [556, 441];
+
+#[derive(Debug, Clone)]
+pub struct ReverbState {
+    combs: [(Line, f32); 4],
+    allpasses: [Line; 2],
+}
+
+impl ReverbState {
+    pub fn new(rate: f32) -> Self {
+        let scaled = |len: usize| Line::new((len as f32 * rate / 44100.0) as usize);
+        Self {
+            combs: COMBS.map(|len| (scaled(len), 0.0)),
+            allpasses: ALLPASSES.map(scaled),
+        }
+    }
+
+    /// Only the wet signal, to be added onto the dry mix
+    pub fn next(&mut self, reverb: &Reverb, input: f32) -> f32 {
+        let feedback = 0.7 + 0.28 * reverb.size;
+        let mut wet = 0.0;
+        for (line, lowpass) in self.combs.iter_mut() {
+            let out = line.read();
+            *lowpass = out + (*lowpass - out) * reverb.damping;
+            line.write(input + *lowpass * feedback);
+            wet += out;
+        }
+
+        wet *= 0.25;
+        for line in self.allpasses.iter_mut() {
+            let delayed = line.read();
+            line.write(wet + delayed * 0.5);
+            wet = delayed - wet;
+        }
+
+        wet * reverb.mix
+    }
+}
@@ -0,0 +1,30 @@
+#![no_std]
+pub mod envelope;
+pub mod filter;
+pub mod fx;
+pub mod osc;
+pub mod song;
+pub mod wav;
+
+pub use envelope::{Envelope, EnvelopeState};
+pub use filter::{Filter, FilterMode, FilterState};
+pub use fx::{Delay, DelayState, Reverb, ReverbState};
+pub use osc::{Oscillator, Waveform};
+pub use song::{render, Instrument, Song, Synth, Track, NOTE_OFF};
+pub use wav::wav;
+
+/// `sin(2π·phase)` for `phase` in 0..1. A plain polynomial instead of libm, so renders come out
+/// bit-identical wherever they run
+pub fn sine(phase: f32) -> f32 {
+    let x = 2.0 * phase - 1.0;
+    let y = -4.0 * x * (1.0 - abs(x));
+    0.225 * (y * abs(y) - y) + y
+}
+
+pub(crate) fn abs(x: f32) -> f32 {
+    if x < 0.0 {
+        -x
+    } else {
+        x
+    }
+}
@@ -0,0 +1,59 @@
+use crate::{abs, sine};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Waveform {
+    Sine,
+    Square,
+    Saw,
+    Triangle,
+    Noise,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Oscillator {
+    waveform: Waveform,
+    phase: f32,
+    seed: u32,
+}
+
+impl Oscillator {
+    pub fn new(waveform: Waveform) -> Self {
+        Self {
+            waveform,
+            phase: 0.0,
+            seed: 0x2545_F491,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.waveform);
+    }
+
+    pub fn next(&mut self, freq: f32, rate: f32) -> f32 {
+        let phase = self.phase;
+        self.phase += freq / rate;
+        if self.phase >= 1.0 {
+            self.phase -= 1.0;
+        }
+
+        match self.waveform {
+            Waveform::Sine => sine(phase),
+            Waveform::Square => {
+                if phase < 0.5 {
+                    1.0
+                } else {
+                    -1.0
+                }
+            }
+            Waveform::Saw => 2.0 * phase - 1.0,
+            Waveform::Triangle => 4.0 * abs(phase - 0.5) - 1.0,
+            // xorshift32, seeded the same every time
+            Waveform::Noise => {
+                self.seed ^= self.seed << 13;
+                self.seed ^= self.seed >> 17;
+                self.seed ^= self.seed << 5;
+                self.seed as i32 as f32 / 2_147_483_648.0
+            }
+        }
+    }
+}
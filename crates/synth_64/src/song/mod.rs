@@ -0,0 +1,217 @@
+use crate::{
+    Delay, DelayState, Envelope, EnvelopeState, Filter, FilterState, Oscillator, Reverb,
+    ReverbState, Waveform,
+};
+use base_64::mem::Vec;
+
+/// Pattern cell that releases the playing note; 0 leaves it alone, anything else is a MIDI note
+pub const NOTE_OFF: u8 = 0xFF;
+
+// MIDI notes 0 to 11, higher octaves are exact doublings
+const OCTAVE: [f32; 12] = [
+    8.175799, 8.661957, 9.177024, 9.722718, 10.300861, 10.913382, 11.562326, 12.249857, 12.978272,
+    13.75, 14.567618, 15.433853,
+];
+
+fn note_freq(note: u8) -> f32 {
+    OCTAVE[note as usize % 12] * (1u32 << (note / 12)) as f32
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Instrument {
+    pub waveform: Waveform,
+    pub envelope: Envelope,
+    pub filter: Option<Filter>,
+    pub volume: f32,
+    /// -1 is hard left, 1 hard right
+    pub pan: f32,
+}
+
+/// `order` lists which pattern plays at each song position
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Track<'a> {
+    pub instrument: u8,
+    pub order: &'a [u8],
+}
+
+/// Everything is plain data so songs can live in statics
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Song<'a> {
+    pub bpm: u16,
+    pub rows_per_beat: u8,
+    pub pattern_rows: u8,
+    pub instruments: &'a [Instrument],
+    pub patterns: &'a [&'a [u8]],
+    pub tracks: &'a [Track<'a>],
+    pub delay: Option<Delay>,
+    pub reverb: Option<Reverb>,
+}
+
+impl Song<'_> {
+    pub fn rows(&self) -> usize {
+        let positions = self.tracks.iter().map(|track| track.order.len()).max();
+        positions.unwrap_or(0) * self.pattern_rows as usize
+    }
+
+    /// First frame of `row`, in integers so rows never drift off the sample grid
+    pub fn row_frame(&self, row: usize, rate: u32) -> u64 {
+        row as u64 * rate as u64 * 60 / (self.bpm as u64 * self.rows_per_beat as u64)
+    }
+
+    pub fn frames(&self, rate: u32) -> u64 {
+        self.row_frame(self.rows(), rate)
+    }
+
+    fn cell(&self, track: &Track, row: usize) -> u8 {
+        let rows = self.pattern_rows as usize;
+        track
+            .order
+            .get(row / rows)
+            .and_then(|&pattern| self.patterns.get(pattern as usize))
+            .and_then(|pattern| pattern.get(row % rows))
+            .copied()
+            .unwrap_or(0)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Voice {
+    osc: Oscillator,
+    envelope: EnvelopeState,
+    filter: FilterState,
+    freq: f32,
+}
+
+/// Renders a song as interleaved stereo
+#[derive(Debug, Clone)]
+pub struct Synth<'a> {
+    song: &'a Song<'a>,
+    rate: u32,
+    frame: u64,
+    row: usize,
+    voices: Vec<Voice>,
+    delay: Option<[DelayState; 2]>,
+    reverb: Option<ReverbState>,
+}
+
+impl<'a> Synth<'a> {
+    pub fn new(song: &'a Song<'a>, rate: u32) -> Self {
+        let voices = song
+            .tracks
+            .iter()
+            .map(|track| Voice {
+                osc: Oscillator::new(song.instruments[track.instrument as usize].waveform),
+                envelope: EnvelopeState::new(),
+                filter: FilterState::new(),
+                freq: 0.0,
+            })
+            .collect();
+
+        Self {
+            song,
+            rate,
+            frame: 0,
+            row: 0,
+            voices,
+            delay: song.delay.map(|delay| {
+                let line = DelayState::new(&delay, rate as f32);
+                [line.clone(), line]
+            }),
+            reverb: song.reverb.map(|_| ReverbState::new(rate as f32)),
+        }
+    }
+
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Past the last row; voices and effects may still be ringing out
+    pub fn is_finished(&self) -> bool {
+        self.row >= self.song.rows()
+    }
+
+    fn trigger(&mut self, row: usize) {
+        let rate = self.rate as f32;
+        for (track, voice) in self.song.tracks.iter().zip(self.voices.iter_mut()) {
+            let instrument = &self.song.instruments[track.instrument as usize];
+            match self.song.cell(track, row) {
+                0 => {}
+                NOTE_OFF => voice.envelope.gate_off(&instrument.envelope, rate),
+                note => {
+                    voice.freq = note_freq(note);
+                    voice.envelope.gate_on();
+                }
+            }
+        }
+    }
+
+    /// One frame of stereo
+    pub fn next_frame(&mut self) -> [f32; 2] {
+        let song = self.song;
+        let rate = self.rate as f32;
+        let rows = song.rows();
+        while self.row < rows && song.row_frame(self.row, self.rate) <= self.frame {
+            self.trigger(self.row);
+            self.row += 1;
+            // Let everything ring out once the song is over
+            if self.row == rows {
+                for (track, voice) in song.tracks.iter().zip(self.voices.iter_mut()) {
+                    let instrument = &song.instruments[track.instrument as usize];
+                    voice.envelope.gate_off(&instrument.envelope, rate);
+                }
+            }
+        }
+
+        let mut out = [0.0; 2];
+        for (track, voice) in song.tracks.iter().zip(self.voices.iter_mut()) {
+            if voice.envelope.is_idle() {
+                continue;
+            }
+
+            let instrument = &song.instruments[track.instrument as usize];
+            let mut sample = voice.osc.next(voice.freq, rate);
+            sample *= voice.envelope.next(&instrument.envelope, rate);
+            if let Some(filter) = &instrument.filter {
+                sample = voice.filter.next(filter, sample, rate);
+            }
+
+            sample *= instrument.volume;
+            out[0] += sample * (1.0 - instrument.pan).min(1.0);
+            out[1] += sample * (1.0 + instrument.pan).min(1.0);
+        }
+
+        if let (Some(delay), Some(lines)) = (&song.delay, &mut self.delay) {
+            out[0] = lines[0].next(delay, out[0]);
+            out[1] = lines[1].next(delay, out[1]);
+        }
+        if let (Some(reverb), Some(state)) = (&song.reverb, &mut self.reverb) {
+            let wet = state.next(reverb, 0.5 * (out[0] + out[1]));
+            out[0] += wet;
+            out[1] += wet;
+        }
+
+        self.frame += 1;
+        out
+    }
+
+    /// Fills interleaved stereo, e.g. straight into an audio callback
+    pub fn fill(&mut self, out: &mut [f32]) {
+        for frame in out.chunks_exact_mut(2) {
+            let [left, right] = self.next_frame();
+            frame[0] = left;
+            frame[1] = right;
+        }
+    }
+}
+
+/// The whole song plus `tail` seconds of ring-out, as interleaved stereo. Identical on every
+/// run and every machine, so renders can be compared sample for sample
+pub fn render(song: &Song, rate: u32, tail: f32) -> Vec<f32> {
+    let frames = song.frames(rate) as usize + (tail * rate as f32) as usize;
+    let mut out: Vec<f32> = (0..frames * 2).map(|_| 0.0).collect();
+    Synth::new(song, rate).fill(&mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,88 @@
+use super::{render, Instrument, Song, Synth, Track, NOTE_OFF};
+use crate::{Delay, Envelope, Filter, FilterMode, Reverb, Waveform};
+
+const RATE: u32 = 8000;
+
+static INSTRUMENTS: [Instrument; 2] = [
+    Instrument {
+        waveform: Waveform::Square,
+        envelope: Envelope::new(0.01, 0.05, 0.5, 0.1),
+        filter: Some(Filter::new(FilterMode::LowPass, 1200.0, 0.3)),
+        volume: 0.4,
+        pan: -0.5,
+    },
+    Instrument {
+        waveform: Waveform::Noise,
+        envelope: Envelope::new(0.0, 0.02, 0.0, 0.01),
+        filter: None,
+        volume: 0.2,
+        pan: 0.5,
+    },
+];
+
+static SONG: Song = Song {
+    bpm: 240,
+    rows_per_beat: 2,
+    pattern_rows: 4,
+    instruments: &INSTRUMENTS,
+    patterns: &[&[60, 0, 64, NOTE_OFF], &[36, 36, 0, 36]],
+    tracks: &[
+        Track {
+            instrument: 0,
+            order: &[0, 0],
+        },
+        Track {
+            instrument: 1,
+            order: &[1],
+        },
+    ],
+    delay: Some(Delay::new(0.05, 0.4, 0.3)),
+    reverb: Some(Reverb::new(0.5, 0.4, 0.2)),
+};
+
+// FNV-1a over the bits of every sample
+fn checksum(samples: &[f32]) -> u64 {
+    samples.iter().fold(0xcbf2_9ce4_8422_2325, |hash, sample| {
+        (hash ^ sample.to_bits() as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[test]
+fn length() {
+    // 8 rows at 8 a second, plus a quarter second of tail
+    assert_eq!(SONG.rows(), 8);
+    assert_eq!(SONG.frames(RATE), 8000);
+    assert_eq!(render(&SONG, RATE, 0.25).len(), 10000 * 2);
+}
+
+#[test]
+fn deterministic() {
+    let first = render(&SONG, RATE, 0.25);
+    let second = render(&SONG, RATE, 0.25);
+    assert!(first
+        .iter()
+        .zip(second.iter())
+        .all(|(a, b)| a.to_bits() == b.to_bits()));
+
+    // Streaming frame by frame gives the same samples as rendering in one go
+    let mut synth = Synth::new(&SONG, RATE);
+    for frame in first.chunks_exact(2).take(4000) {
+        let [left, right] = synth.next_frame();
+        assert_eq!(
+            (left.to_bits(), right.to_bits()),
+            (frame[0].to_bits(), frame[1].to_bits())
+        );
+    }
+}
+
+#[test]
+fn golden() {
+    let samples = render(&SONG, RATE, 0.25);
+    assert_eq!(checksum(&samples), 0xc1b6_e2ed_fe3f_856c);
+
+    // The noise hit, panned right, has no attack, while the square to the left fades in
+    assert_eq!(samples[0].to_bits(), 0xbcc5_7b72);
+    assert_eq!(samples[1].to_bits(), 0xbd45_7b72);
+    assert_eq!(samples[1001 * 2].to_bits(), 0xbeae_aff7);
+    assert_eq!(samples[1001 * 2 + 1].to_bits(), 0xbe27_4e45);
+}
@@ -1,48 +1,65 @@
+use core::f32::consts::TAU;
 use underscore_64::{
+    app::{App, Context, Runner},
     c_str,
     gfx::{
-        mesh::{Mesh, Topology},
-        program::Program,
-        shader::{POS2D, WHITE},
-        Resource, Target,
+        resource::shader::{POS2D, WHITE},
+        Draw, Mesh, Pipeline, Primitive, Resource, Usage, WindowBuilder,
     },
     math::{sin, Curve},
-    sdl::{
-        event::{Event, EventFeed},
-        window::Window,
-    },
 };
 
-static NAME: &[u8] = c_str!("_64-triangle");
+static NAME: &[u8] = c_str!("_64-curves");
+const SAMPLES: usize = 100;
 
-pub fn main() {
-    let window = Window::new(NAME, 1920, 1080).expect("test");
+struct Curves {
+    pipeline: Pipeline,
+    mesh: Mesh,
+    phase: f32,
+    last_phase: f32,
+}
 
-    let program = Program::new(POS2D, WHITE);
-    program.bind();
+impl Curves {
+    fn plot(phase: f32) -> impl Fn(f32) -> f32 {
+        move |x: f32| sin((x + phase) * TAU)
+    }
+}
 
-    let new_sin = |x: f32| sin(x * 6.28);
-    let sin_plot = new_sin.plot(-1.0, 1.0, 100);
+impl App for Curves {
+    fn init(_: &mut Context) -> Self {
+        let pipeline = Pipeline::new(POS2D, WHITE).expect("shader error");
+        let mesh = Mesh::new(
+            &Self::plot(0.0).plot(-1.0, 1.0, SAMPLES),
+            Usage::StreamDraw,
+            Primitive::LineStrip,
+        );
 
-    let mesh = Mesh::static_draw(&sin_plot, Topology::Lines);
+        Self {
+            pipeline,
+            mesh,
+            phase: 0.0,
+            last_phase: 0.0,
+        }
+    }
 
-    let mut events = EventFeed;
-    loop {
-        match events.next() {
-            Some(event) => match event {
-                Event::Quit => {
-                    break;
-                }
+    fn update(&mut self, _: &mut Context, dt: f32) {
+        self.last_phase = self.phase;
+        self.phase += 0.25 * dt;
+    }
 
-                _ => {}
-            },
+    fn draw(&mut self, _: &mut Context, alpha: f32) {
+        let phase = self.last_phase + (self.phase - self.last_phase) * alpha;
+        self.mesh
+            .update(&Self::plot(phase).plot(-1.0, 1.0, SAMPLES));
 
-            None => {
-                window.clear_color([0.0, 0.0, 0.0, 1.0]);
-                program.bind();
-                mesh.draw();
-                window.swap();
-            }
-        }
+        self.pipeline.bind();
+        self.mesh.draw();
     }
 }
+
+pub fn main() {
+    Runner::new(WindowBuilder::new(NAME, 1920, 1080))
+        .update_rate(30.0)
+        .run::<Curves>()
+        .expect("failed to open sdl2 window");
+}
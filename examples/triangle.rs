@@ -1,52 +1,44 @@
 use underscore_64::{
+    app::{App, Context, Runner},
     c_str,
     gfx::{
-        mesh::{Mesh, Topology, Usage},
-        program::Program,
-        shader::{POS2D_RGB, RGB},
-        Resource, Target,
-    },
-    sdl::{
-        event::{Event, EventFeed},
-        window::Window,
+        resource::shader::{POS2D_RGB, RGB},
+        Draw, Mesh, Pipeline, Primitive, Resource, Usage, WindowBuilder,
     },
 };
 
 static NAME: &[u8] = c_str!("_64-triangle");
 
-pub fn main() {
-    let window = Window::new(NAME, 1920, 1080).expect("failed to open sdl2 window");
-
-    let program = Program::new(POS2D_RGB, RGB);
-    program.bind();
-
-    let mesh = Mesh::new(
-        &[
-            ([0.0, 1.0], [1.0, 0.0, 0.0]),
-            ([1.0, -1.0], [0.0, 1.0, 0.0]),
-            ([-1.0, -1.0], [0.0, 0.0, 1.0]),
-        ],
-        Usage::StaticDraw,
-        Topology::from_indices(&[0, 1, 2], Usage::StaticDraw),
-    );
+struct Triangle {
+    pipeline: Pipeline,
+    mesh: Mesh,
+}
 
-    let mut events = EventFeed;
-    loop {
-        match events.next() {
-            Some(event) => match event {
-                Event::Quit => {
-                    break;
-                }
+impl App for Triangle {
+    fn init(_: &mut Context) -> Self {
+        let pipeline = Pipeline::new(POS2D_RGB, RGB).expect("shader error");
+        let mesh = Mesh::new(
+            &[
+                ([0.0, 1.0], [1.0, 0.0, 0.0]),
+                ([1.0, -1.0], [0.0, 1.0, 0.0]),
+                ([-1.0, -1.0], [0.0, 0.0, 1.0]),
+            ],
+            Usage::StaticDraw,
+            Primitive::TriFan,
+        );
 
-                _ => {}
-            },
+        Self { pipeline, mesh }
+    }
 
-            None => {
-                window.clear_color([0.0, 0.0, 0.0, 1.0]);
-                program.bind();
-                mesh.draw();
-                window.swap();
-            }
-        }
+    fn draw(&mut self, _: &mut Context, _: f32) {
+        self.pipeline.bind();
+        self.mesh.draw();
     }
 }
+
+pub fn main() {
+    Runner::new(WindowBuilder::new(NAME, 1920, 1080))
+        .frame_limit(60.0)
+        .run::<Triangle>()
+        .expect("failed to open sdl2 window");
+}
@@ -0,0 +1,182 @@
+use gfx_64::{Error, GfxSystem, WindowBuilder};
+use sdl_64::{
+    event::{Event, EventFeed, WindowEvent},
+    input::InputState,
+    time::Clock,
+};
+
+// Longest frame fed to the simulation, so a stall doesn't turn into a burst of catch-up steps
+const MAX_FRAME_TIME: f64 = 0.25;
+// Weight of the newest frame in the smoothed frame rate
+const FPS_SMOOTHING: f32 = 0.1;
+
+pub trait App: Sized {
+    /// Called once the window and GL context are up
+    fn init(ctx: &mut Context) -> Self;
+
+    /// Every event, after `ctx.input` has seen it; quit and resize are handled by the runner
+    fn event(&mut self, _ctx: &mut Context, _event: &Event) {}
+
+    /// Fixed-step simulation, `dt` is always the runner's step
+    fn update(&mut self, _ctx: &mut Context, _dt: f32) {}
+
+    /// `alpha` is how far between the last two updates this frame falls, for interpolation
+    fn draw(&mut self, _ctx: &mut Context, _alpha: f32) {}
+
+    fn shutdown(&mut self, _ctx: &mut Context) {}
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FrameStats {
+    pub frame: u64,
+    // Seconds, measured from the start of one frame to the next
+    pub frame_time: f32,
+    pub update_time: f32,
+    pub draw_time: f32,
+    pub updates: u32,
+    pub fps: f32,
+}
+
+pub struct Context {
+    pub gfx: GfxSystem,
+    pub input: InputState,
+    stats: FrameStats,
+    time: f64,
+    quit: bool,
+}
+
+impl Context {
+    pub fn quit(&mut self) {
+        self.quit = true;
+    }
+
+    pub fn stats(&self) -> FrameStats {
+        self.stats
+    }
+
+    /// Simulated seconds, advanced by each fixed update
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+}
+
+pub struct Runner<'a> {
+    window: WindowBuilder<'a>,
+    step: f64,
+    max_updates: u32,
+    frame_limit: Option<f64>,
+}
+
+impl<'a> Runner<'a> {
+    pub fn new(window: WindowBuilder<'a>) -> Self {
+        Self {
+            window,
+            step: 1.0 / 60.0,
+            max_updates: 8,
+            frame_limit: None,
+        }
+    }
+
+    pub fn update_rate(self, hz: f64) -> Self {
+        Self {
+            step: 1.0 / hz,
+            ..self
+        }
+    }
+
+    /// Most fixed updates run in one frame before the simulation is allowed to fall behind
+    pub fn max_updates(self, max_updates: u32) -> Self {
+        Self {
+            max_updates,
+            ..self
+        }
+    }
+
+    pub fn frame_limit(self, fps: f64) -> Self {
+        Self {
+            frame_limit: Some(1.0 / fps),
+            ..self
+        }
+    }
+
+    pub fn run<A: App>(self) -> Result<(), Error> {
+        let mut ctx = Context {
+            gfx: GfxSystem::new(self.window)?,
+            input: InputState::new(),
+            stats: FrameStats::default(),
+            time: 0.0,
+            quit: false,
+        };
+        let mut events = EventFeed::new();
+        let mut app = A::init(&mut ctx);
+
+        let mut clock = Clock::new();
+        let mut accumulator = 0.0;
+        clock.tick();
+        while !ctx.quit {
+            let frame_start = clock.now();
+            let frame_time = clock.tick();
+            accumulator += frame_time.min(MAX_FRAME_TIME);
+
+            ctx.input.begin_frame();
+            for event in &mut events {
+                ctx.input.handle(&event);
+                match event {
+                    Event::Quit => ctx.quit(),
+                    Event::Window {
+                        event: WindowEvent::SizeChanged { .. },
+                        ..
+                    } => {
+                        ctx.gfx.resize();
+                    }
+                    _ => {}
+                }
+                app.event(&mut ctx, &event);
+            }
+
+            let mut updates = 0;
+            while accumulator >= self.step && updates < self.max_updates {
+                app.update(&mut ctx, self.step as f32);
+                accumulator -= self.step;
+                ctx.time += self.step;
+                updates += 1;
+            }
+            if updates == self.max_updates && accumulator >= self.step {
+                #[cfg(feature = "log")]
+                log::debug!("simulation behind, dropping {:.3}s", accumulator);
+                accumulator %= self.step;
+            }
+            let update_end = clock.now();
+
+            ctx.gfx.begin();
+            app.draw(&mut ctx, (accumulator / self.step) as f32);
+            ctx.gfx.present();
+            let draw_end = clock.now();
+
+            if let Some(limit) = self.frame_limit {
+                clock.wait_until(frame_start + limit);
+            }
+
+            let stats = &mut ctx.stats;
+            let fps = if frame_time > 0.0 {
+                1.0 / frame_time as f32
+            } else {
+                0.0
+            };
+            *stats = FrameStats {
+                frame: stats.frame + 1,
+                frame_time: frame_time as f32,
+                update_time: (update_end - frame_start) as f32,
+                draw_time: (draw_end - update_end) as f32,
+                updates,
+                fps: match stats.frame {
+                    0 => fps,
+                    _ => stats.fps + (fps - stats.fps) * FPS_SMOOTHING,
+                },
+            };
+        }
+
+        app.shutdown(&mut ctx);
+        Ok(())
+    }
+}
@@ -1,7 +1,11 @@
 #![no_std]
+pub mod app;
+
 pub use base_64::math;
 pub use gfx_64 as gfx;
 pub use gui_64 as gui;
+pub use sdl_64 as sdl;
+pub use synth_64 as synth;
 
 #[macro_export]
 macro_rules! c_str {
@@ -29,21 +29,13 @@ static NAME: &[u8] = c_str!("_64");
 const WIDTH: i32 = 1920;
 const HEIGHT: i32 = 1080;
 
-use base_64::graph::Node;
-use gfx_64::{
-    resource::{
-        mesh::{Mesh, Topology, Usage},
-        pipeline::Pipeline,
-        shader::{POS2D_TEX2D, TEX2D},
-    },
-    Draw, GfxSystem, RenderTarget, Resource,
+use gfx_64::{Draw, SwapInterval, WindowBuilder};
+use gui_64::{font::FontId, GuiSystem, HACK_TTF};
+use sdl_64::event::{Event, WindowEvent};
+use underscore_64::{
+    app::{App, Context, Runner},
+    c_str,
 };
-use gui_64::{GuiSystem, HACK_TTF};
-use sdl_64::{
-    event::{Event, EventFeed},
-    window::Window,
-};
-use underscore_64::c_str;
 
 #[cfg(feature = "log")]
 mod simple_log {
@@ -75,62 +67,59 @@ mod simple_log {
     }
 }
 
-#[cfg_attr(not(feature = "std"), no_mangle)]
-pub fn main() {
-    #[cfg(feature = "log")]
-    simple_log::init();
+struct Demo {
+    gui: GuiSystem<'static>,
+    hack: FontId,
+}
 
-    let gfx = Node::new(GfxSystem::new(NAME, 1920, 1080).expect("couldn't open SDL2/GL window"));
-    let gui = Node::new(GuiSystem::new([WIDTH, HEIGHT]));
+impl Demo {
+    fn draw_hud(&mut self) {
+        let [_, height] = self.gui.dim();
+        self.gui
+            .draw_text(self.hack, "hello\nworld", [0.0, height], 3.0, [1.0; 4]);
+    }
+}
 
-    let mut gfx = gfx.handle();
-    let mut gui = gui.handle();
+impl App for Demo {
+    fn init(ctx: &mut Context) -> Self {
+        let mut gui = GuiSystem::new(ctx.gfx.drawable_size()).expect("gui setup failed");
+        let hack = gui.load_font(HACK_TTF).expect("load font failed");
 
-    let hack = gui.load_font(HACK_TTF).expect("load font failed");
-    gui.draw_text(hack, "hello\nworld", [0.0, HEIGHT as f32], 3.0);
+        let mut demo = Self { gui, hack };
+        demo.draw_hud();
 
-    let tex_quad = Mesh::new(
-        &[
-            ([-1.0, 1.0], [0.0, 1.0]),
-            ([1.0, 1.0], [1.0, 1.0]),
-            ([-1.0, -1.0], [0.0, 0.0]),
-            ([1.0, -1.0], [1.0, 0.0]),
-        ],
-        Usage::StaticDraw,
-        Topology::TriStrip,
-    );
+        #[cfg(feature = "log")]
+        log::info!("main setup completed");
+        demo
+    }
 
-    let glyph_prog = Pipeline::new(POS2D_TEX2D, TEX2D);
+    fn event(&mut self, ctx: &mut Context, event: &Event) {
+        if let Event::Window {
+            event: WindowEvent::SizeChanged { .. },
+            ..
+        } = event
+        {
+            self.gui.resize(ctx.gfx.drawable_size());
+            self.draw_hud();
+        }
+    }
 
-    let mut events = EventFeed::new();
-    events.text_input(true);
+    fn draw(&mut self, _: &mut Context, _: f32) {
+        self.gui.draw();
+    }
+}
 
+#[cfg_attr(not(feature = "std"), no_mangle)]
+pub fn main() {
     #[cfg(feature = "log")]
-    log::info!("main setup completed");
-    #[cfg(feature = "log")]
-    log::set_max_level(log::LevelFilter::Off);
+    simple_log::init();
 
-    let mut frame = 0;
-    loop {
-        #[cfg(feature = "log")]
-        log::info!("starting frame {}", frame);
-        match events.next() {
-            Some(event) => match event {
-                Event::Quit => {
-                    #[cfg(feature = "log")]
-                    log::info!("quit event received");
-                    break;
-                }
-
-                _ => {}
-            },
-
-            None => {}
-        };
-
-        gfx.draw(|| gui.draw());
-        frame += 1;
-    }
+    let window = WindowBuilder::new(NAME, WIDTH, HEIGHT)
+        .resizable(true)
+        .swap_interval(SwapInterval::VSync);
+    Runner::new(window)
+        .run::<Demo>()
+        .expect("couldn't open SDL2/GL window");
 
     #[cfg(not(feature = "std"))]
     unsafe {